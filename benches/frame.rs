@@ -0,0 +1,56 @@
+//! Per tick cost of building a frame: the old `Vec<Vec<String>>` frame that was
+//! allocated every tick and cloned again by the render thread, against the flat
+//! `Frame` handed out and recycled by `FramePool`.
+//!
+//! Run with `cargo bench --bench frame`.
+use std::{time::{Duration, Instant}, hint::black_box};
+
+use invaders::{frame::{Drawable, FramePool}, game::Game, Playfield, NUM_COLS, NUM_ROWS};
+
+const TICKS: u32 = 20_000;
+
+fn time_ticks(name: &str, mut tick: impl FnMut()) -> Duration {
+    // warm up allocator and caches
+    for _ in 0..TICKS / 10 {
+        tick();
+    }
+    let start = Instant::now();
+    for _ in 0..TICKS {
+        tick();
+    }
+    let per_tick = start.elapsed() / TICKS;
+    println!("{:<24} {:>10?} per tick", name, per_tick);
+    per_tick
+}
+
+fn main() {
+    let field = Playfield::default();
+    let game = Game::with_seed(1);
+
+    let legacy = time_ticks("Vec<Vec<String>> frame", || {
+        let mut frame: Vec<Vec<String>> = Vec::with_capacity(NUM_COLS);
+        for _ in 0..NUM_COLS {
+            let mut col = Vec::with_capacity(NUM_ROWS);
+            for _ in 0..NUM_ROWS {
+                col.push(" ".to_string());
+            }
+            frame.push(col);
+        }
+        for invader in game.invaders.army.iter() {
+            frame[invader.x][invader.y] = "x".to_string();
+        }
+        // the render thread kept its own copy of every frame
+        let last_frame = frame.to_vec();
+        black_box(last_frame);
+    });
+
+    let (frames, recycle_tx) = FramePool::new();
+    let pooled = time_ticks("pooled Frame", || {
+        let mut frame = frames.next(field);
+        game.draw(&mut frame);
+        black_box(&frame);
+        let _ = recycle_tx.send(frame);
+    });
+
+    println!("pooled frames take {:.1}% of the legacy per tick cost", pooled.as_secs_f64() / legacy.as_secs_f64() * 100.0);
+}
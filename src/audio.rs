@@ -0,0 +1,450 @@
+use std::{fs, error::Error, io::Cursor, collections::HashMap, time::{Duration, Instant}, sync::{Arc, atomic::{AtomicU64, Ordering}}};
+use rodio::{Decoder, Sink, OutputStream, OutputStreamHandle, Source, source::Buffered};
+use serde::{Serialize, Deserialize};
+
+/// voices opened with the device, enough for a few overlapping effects
+pub const VOICE_COUNT: usize = 8;
+/// voices added on demand when rapid sounds overlap, past this a lower priority voice is taken over
+pub const MAX_VOICES: usize = 32;
+
+/// Channel group a sound plays in, each group has its own volume and can be stopped on its own.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum Group {
+    Sfx,
+    Music,
+    Ui
+}
+
+impl Group {
+    pub const ALL: [Group; 3] = [Group::Sfx, Group::Music, Group::Ui];
+}
+
+/// sample rate of the synthesized music
+const MUSIC_RATE: u32 = 44100;
+/// how long switching between tracks takes
+pub const CROSS_FADE: Duration = Duration::from_millis(600);
+/// longest a note of the music sounds before it goes quiet until the next beat
+const NOTE_LENGTH: Duration = Duration::from_millis(150);
+pub const MENU_BEAT: Duration = Duration::from_millis(450);
+
+/// Looping background music, synthesized rather than loaded from a file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Track {
+    Menu,
+    /// the arcade's four note march, its beat follows the army, see `Audio::set_tempo`
+    Game
+}
+
+impl Track {
+    /**
+     * desc: frequencies of the notes played one per beat, in Hz
+     */
+    fn notes(&self) -> &'static [f32] {
+        match self {
+            Track::Menu => &[220.0, 261.63, 329.63, 261.63, 196.0, 246.94, 293.66, 246.94],
+            Track::Game => &[110.0, 98.0, 92.5, 82.41]
+        }
+    }
+    fn loudness(&self) -> f32 {
+        match self {
+            Track::Menu => 0.08,
+            Track::Game => 0.2
+        }
+    }
+}
+
+/// A track as an endless source, a square wave note at the start of every beat.
+struct Melody {
+    notes: &'static [f32],
+    loudness: f32,
+    /// beat length in microseconds, shared so the tempo can change while it plays
+    beat: Arc<AtomicU64>,
+    note: usize,
+    sample: u64,
+    phase: f32
+}
+
+impl Melody {
+    fn new(track: Track, beat: Arc<AtomicU64>) -> Self {
+        Self { notes: track.notes(), loudness: track.loudness(), beat, note: 0, sample: 0, phase: 0.0 }
+    }
+}
+
+impl Iterator for Melody {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        let beat = (self.beat.load(Ordering::Relaxed) * MUSIC_RATE as u64 / 1_000_000).max(1);
+        if self.sample >= beat {
+            self.sample = 0;
+            self.note = (self.note + 1) % self.notes.len();
+        }
+        // notes last at most half a beat so fast marches stay separate notes
+        let length = (NOTE_LENGTH.as_micros() as u64 * MUSIC_RATE as u64 / 1_000_000).min(beat / 2).max(1);
+        let value = if self.sample < length {
+            let fade = 1.0 - self.sample as f32 / length as f32;
+            let square = if self.phase < 0.5 { 1.0 } else { -1.0 };
+            square * fade * self.loudness
+        } else {
+            0.0
+        };
+        self.phase = (self.phase + self.notes[self.note] / MUSIC_RATE as f32) % 1.0;
+        self.sample += 1;
+        Some(value)
+    }
+}
+
+impl Source for Melody {
+    fn current_frame_len(&self) -> Option<usize> {
+        None
+    }
+    fn channels(&self) -> u16 {
+        1
+    }
+    fn sample_rate(&self) -> u32 {
+        MUSIC_RATE
+    }
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+}
+
+/// Sound output for the game, `open` picks a backend that works on this machine.
+pub trait Audio {
+    /**
+     * desc: loads and decodes the sound at `path` so `play(name)` can start it straight away
+     */
+    fn add(&mut self, name: &'static str, path: &'static str, group: Group, priority: u8) -> Result<(), Box<dyn Error>>;
+    /**
+     * desc: starts the sound, unknown names are ignored
+     */
+    fn play(&mut self, key: &'static str);
+    /**
+     * desc: stops every sound playing in `group`
+     */
+    fn stop(&mut self, group: Group);
+    fn volume(&self, group: Group) -> f32;
+    /**
+     * desc: sets the volume of `group`, 1.0 plays sounds as recorded
+     */
+    fn set_volume(&mut self, group: Group, volume: f32);
+    fn muted(&self) -> bool;
+    fn set_muted(&mut self, muted: bool);
+    /**
+     * desc: short text for the corner of the screen, `None` while sound plays normally
+     */
+    fn status(&self) -> Option<&'static str>;
+    /**
+     * desc: fades over to `track`, `None` fades the music out, a track faded out is paused where it was
+     */
+    fn play_music(&mut self, track: Option<Track>);
+    /**
+     * desc: time between notes of the game track
+     */
+    fn set_tempo(&mut self, beat: Duration);
+    /**
+     * desc: moves the music fades along, called every frame
+     */
+    fn update(&mut self);
+    fn close(self: Box<Self>);
+
+    fn toggle_mute(&mut self) {
+        let muted = self.muted();
+        self.set_muted(!muted);
+    }
+    fn stop_all(&mut self) {
+        for group in Group::ALL {
+            self.stop(group);
+        }
+    }
+}
+
+/**
+ * desc: rodio audio on the default device, silent audio if there is none or `no_audio` is set
+ */
+pub fn open(no_audio: bool, muted: bool) -> Box<dyn Audio> {
+    let mut audio: Box<dyn Audio> = if no_audio {
+        Box::new(SilentAudio::new())
+    } else {
+        match RodioAudio::new() {
+            Ok(audio) => Box::new(audio),
+            Err(_) => Box::new(SilentAudio::new())
+        }
+    };
+    audio.set_muted(muted);
+    audio
+}
+
+/// A sound decoded once at load time, clones share the decoded samples.
+type Sound = Buffered<Decoder<Cursor<Vec<u8>>>>;
+
+/**
+ * desc: reads and fully decodes the sound at `path`
+ */
+fn load_sound(path: &str) -> Result<Sound, Box<dyn Error>> {
+    let bytes = match fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(e) => return Err(format!("Couldn't Open {}: {}", path, e).into())
+    };
+    let sound = match Decoder::new(Cursor::new(bytes)) {
+        Ok(decoder) => decoder.buffered(),
+        Err(e) => return Err(format!("Couldn't Decode {}: {}", path, e).into())
+    };
+    // running through the clone once decodes every frame into the shared buffer
+    if sound.clone().count() == 0 {
+        return Err(format!("{} Has No Samples", path).into());
+    }
+    Ok(sound)
+}
+
+/// A loaded sound and how it competes for voices.
+struct Sample {
+    sound: Sound,
+    group: Group,
+    priority: u8
+}
+
+/// A sink playing one sound at a time, remembers what it was given to decide what can take it over.
+struct Voice {
+    sink: Sink,
+    group: Group,
+    priority: u8,
+    started: Instant
+}
+
+/// A track playing on its own sink, `level` follows `target` for the cross-fade.
+struct Music {
+    sink: Sink,
+    track: Track,
+    level: f32,
+    target: f32
+}
+
+/// Plays preloaded sounds through rodio, each on its own voice so they mix instead of queueing.
+pub struct RodioAudio {
+    samples: HashMap<&'static str, Sample>,
+    voices: Vec<Voice>,
+    volumes: HashMap<Group, f32>,
+    music: Vec<Music>,
+    /// beat of the game track in microseconds
+    tempo: Arc<AtomicU64>,
+    last_update: Instant,
+    stream_handle: OutputStreamHandle,
+    muted: bool,
+    // the device closes when the stream is dropped
+    _stream: OutputStream
+}
+
+impl RodioAudio {
+    pub fn new() -> Result<Self, Box<dyn Error>> {
+        let (stream, stream_handle) = OutputStream::try_default()?;
+        // sinks are made up front so a broken device fails here instead of mid game
+        let mut voices = Vec::new();
+        for _ in 0..VOICE_COUNT {
+            voices.push(Voice { sink: Sink::try_new(&stream_handle)?, group: Group::Sfx, priority: 0, started: Instant::now() });
+        }
+        Ok(Self {
+            samples: HashMap::new(),
+            voices,
+            volumes: HashMap::new(),
+            music: Vec::new(),
+            tempo: Arc::new(AtomicU64::new(MENU_BEAT.as_micros() as u64)),
+            last_update: Instant::now(),
+            stream_handle,
+            muted: false,
+            _stream: stream
+        })
+    }
+
+    /**
+     * desc: index of a voice for a sound of `priority`: a free one, a new one while under `MAX_VOICES`,
+     * or the oldest of the lowest priority voices if none of them outranks the sound
+     */
+    fn find_voice(&mut self, priority: u8) -> Option<usize> {
+        if let Some(i) = self.voices.iter().position(|voice| voice.sink.empty()) {
+            return Some(i);
+        }
+        if self.voices.len() < MAX_VOICES {
+            let sink = Sink::try_new(&self.stream_handle).ok()?;
+            self.voices.push(Voice { sink, group: Group::Sfx, priority: 0, started: Instant::now() });
+            return Some(self.voices.len() - 1);
+        }
+        let (i, voice) = self.voices.iter().enumerate().min_by_key(|(_, voice)| (voice.priority, voice.started))?;
+        if voice.priority > priority {
+            return None;
+        }
+        // a stopped sink finishes its queue before taking new sounds, a fresh one starts straight away
+        self.voices[i].sink = Sink::try_new(&self.stream_handle).ok()?;
+        Some(i)
+    }
+}
+
+impl Audio for RodioAudio {
+    fn add(&mut self, name: &'static str, path: &'static str, group: Group, priority: u8) -> Result<(), Box<dyn Error>> {
+        self.samples.insert(name, Sample { sound: load_sound(path)?, group, priority });
+        Ok(())
+    }
+
+    fn play(&mut self, key: &'static str) {
+        if self.muted {
+            return;
+        }
+        let (sound, group, priority) = match self.samples.get(key) {
+            Some(sample) => (sample.sound.clone(), sample.group, sample.priority),
+            None => return
+        };
+        let volume = self.volume(group);
+        if let Some(i) = self.find_voice(priority) {
+            let voice = &mut self.voices[i];
+            voice.group = group;
+            voice.priority = priority;
+            voice.started = Instant::now();
+            voice.sink.set_volume(volume);
+            voice.sink.append(sound);
+        }
+    }
+
+    fn stop(&mut self, group: Group) {
+        for voice in self.voices.iter().filter(|voice| voice.group == group) {
+            voice.sink.stop();
+        }
+        if group == Group::Music {
+            // dropping the sinks stops the tracks, they start over when played again
+            self.music.clear();
+        }
+    }
+
+    fn volume(&self, group: Group) -> f32 {
+        self.volumes.get(&group).copied().unwrap_or(1.0)
+    }
+
+    fn set_volume(&mut self, group: Group, volume: f32) {
+        let volume = volume.max(0.0);
+        self.volumes.insert(group, volume);
+        for voice in self.voices.iter().filter(|voice| voice.group == group) {
+            voice.sink.set_volume(volume);
+        }
+    }
+
+    fn muted(&self) -> bool {
+        self.muted
+    }
+
+    fn set_muted(&mut self, muted: bool) {
+        if muted {
+            // music only pauses so it carries on where it was once unmuted
+            for group in [Group::Sfx, Group::Ui] {
+                self.stop(group);
+            }
+        }
+        self.muted = muted;
+    }
+
+    fn status(&self) -> Option<&'static str> {
+        if self.muted { Some("Muted") } else { None }
+    }
+
+    fn play_music(&mut self, track: Option<Track>) {
+        for music in self.music.iter_mut() {
+            music.target = if Some(music.track) == track { 1.0 } else { 0.0 };
+        }
+        let track = match track {
+            Some(track) if !self.music.iter().any(|music| music.track == track) => track,
+            _ => return
+        };
+        let beat = match track {
+            Track::Menu => Arc::new(AtomicU64::new(MENU_BEAT.as_micros() as u64)),
+            Track::Game => Arc::clone(&self.tempo)
+        };
+        if let Ok(sink) = Sink::try_new(&self.stream_handle) {
+            sink.set_volume(0.0);
+            sink.append(Melody::new(track, beat));
+            self.music.push(Music { sink, track, level: 0.0, target: 1.0 });
+        }
+    }
+
+    fn set_tempo(&mut self, beat: Duration) {
+        self.tempo.store(beat.as_micros() as u64, Ordering::Relaxed);
+    }
+
+    fn update(&mut self) {
+        let now = Instant::now();
+        let step = (now - self.last_update).as_secs_f32() / CROSS_FADE.as_secs_f32();
+        self.last_update = now;
+        let volume = self.volume(Group::Music);
+        for music in self.music.iter_mut() {
+            music.level = if music.level < music.target {
+                (music.level + step).min(music.target)
+            } else {
+                (music.level - step).max(music.target)
+            };
+            music.sink.set_volume(music.level * volume);
+            if self.muted || music.level == 0.0 {
+                music.sink.pause();
+            } else {
+                music.sink.play();
+            }
+        }
+    }
+
+    fn close(mut self: Box<Self>) {
+        self.stop_all();
+    }
+}
+
+/// Audio for machines without a sound device, every sound is dropped.
+pub struct SilentAudio {
+    muted: bool,
+    volumes: HashMap<Group, f32>
+}
+
+impl SilentAudio {
+    pub fn new() -> Self {
+        Self { muted: false, volumes: HashMap::new() }
+    }
+}
+
+impl Default for SilentAudio {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Audio for SilentAudio {
+    fn add(&mut self, _name: &'static str, _path: &'static str, _group: Group, _priority: u8) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    fn play(&mut self, _key: &'static str) {}
+
+    fn stop(&mut self, _group: Group) {}
+
+    fn volume(&self, group: Group) -> f32 {
+        self.volumes.get(&group).copied().unwrap_or(1.0)
+    }
+
+    fn set_volume(&mut self, group: Group, volume: f32) {
+        self.volumes.insert(group, volume.max(0.0));
+    }
+
+    fn muted(&self) -> bool {
+        self.muted
+    }
+
+    fn set_muted(&mut self, muted: bool) {
+        self.muted = muted;
+    }
+
+    fn status(&self) -> Option<&'static str> {
+        Some("No Audio")
+    }
+
+    fn play_music(&mut self, _track: Option<Track>) {}
+
+    fn set_tempo(&mut self, _beat: Duration) {}
+
+    fn update(&mut self) {}
+
+    fn close(self: Box<Self>) {}
+}
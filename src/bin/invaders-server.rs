@@ -0,0 +1,27 @@
+use std::{error::Error, env, process};
+use invaders::{cli::{ServerArgs, SERVER_USAGE}, level::{Level, LEVEL_DIR}, server::{self, SERVER_ADDR, SERVER_DATA}};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    dotenv::dotenv().ok();
+    let args = match ServerArgs::parse(env::args().skip(1)) {
+        Ok(Some(args)) => args,
+        Ok(None) => {
+            println!("{}", SERVER_USAGE);
+            return Ok(());
+        }
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, SERVER_USAGE);
+            process::exit(2);
+        }
+    };
+    let addr = args.addr.unwrap_or_else(|| SERVER_ADDR.to_string());
+    let data = args.data.unwrap_or_else(|| SERVER_DATA.to_string());
+    let api_key = env::var("API_KEY").unwrap_or_default();
+    if api_key.is_empty() {
+        eprintln!("API_KEY Isn't Set, Accepting Every Request");
+    }
+    // proofs are played back with the same level files the game ships with
+    let levels = Level::load_dir(LEVEL_DIR)?;
+    server::run(&addr, &data, api_key, args.allow_unproven, levels).await
+}
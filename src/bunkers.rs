@@ -0,0 +1,109 @@
+use crossterm::style::Color;
+
+use crate::{Playfield, frame::{Drawable, Frame, Cell}, shot::Shot, invaders::Invaders, level::Level};
+
+pub const BUNKER_COUNT: usize = 4;
+pub const BUNKER_WIDTH: usize = 4;
+pub const BUNKER_HEIGHT: usize = 2;
+/// hits a single bunker cell can take before it crumbles
+pub const BUNKER_HEALTH: u8 = 3;
+
+pub struct BunkerCell {
+    pub x: usize,
+    pub y: usize,
+    pub health: u8
+}
+
+pub struct Bunkers {
+    pub cells: Vec<BunkerCell>
+}
+
+impl Bunkers {
+    pub fn new(field: Playfield) -> Self {
+        let mut cells = Vec::new();
+        let spacing = field.cols / BUNKER_COUNT;
+        let margin = (spacing - BUNKER_WIDTH) / 2;
+        // a few rows above the player
+        let top = field.rows - 3 - BUNKER_HEIGHT;
+        for i in 0..BUNKER_COUNT {
+            for x in 0..BUNKER_WIDTH {
+                for y in 0..BUNKER_HEIGHT {
+                    cells.push(BunkerCell { x: i * spacing + margin + x, y: top + y, health: BUNKER_HEALTH });
+                }
+            }
+        }
+        Self { cells }
+    }
+    /**
+     * desc: bunkers from a level file, falls back to the default layout if it has none
+     */
+    pub fn from_level(level: &Level, field: Playfield) -> Self {
+        let grid = match &level.bunkers {
+            Some(grid) => grid,
+            None => return Self::new(field)
+        };
+        let mut cells = Vec::new();
+        // the last line of the grid sits on the bottom bunker row
+        let bottom = field.rows - 4;
+        for (row, line) in grid.iter().rev().enumerate() {
+            if row > bottom {
+                break;
+            }
+            for (x, c) in line.chars().enumerate() {
+                if c == '#' && x < field.cols {
+                    cells.push(BunkerCell { x, y: bottom - row, health: BUNKER_HEALTH });
+                }
+            }
+        }
+        Self { cells }
+    }
+    /**
+     * desc: erodes the cell at x, y, returns true if there was one
+     */
+    pub fn hit_at(&mut self, x: usize, y: usize) -> bool {
+        if let Some(i) = self.cells.iter().position(|cell| cell.x == x && cell.y == y) {
+            self.cells[i].health -= 1;
+            if self.cells[i].health == 0 {
+                self.cells.remove(i);
+            }
+            return true;
+        }
+        false
+    }
+    /**
+     * desc: explodes the shot against a bunker cell, eroding it
+     */
+    pub fn absorb(&mut self, shot: &mut Shot) -> bool {
+        if !shot.exploding && self.hit_at(shot.x, shot.y) {
+            shot.explode();
+            return true;
+        }
+        false
+    }
+    /**
+     * desc: invaders that descend into the bunkers destroy those cells
+     */
+    pub fn crush(&mut self, invaders: &Invaders) {
+        self.cells.retain(|cell| {
+            !invaders.army.iter().any(|invader| invader.x == cell.x && invader.y == cell.y)
+        });
+    }
+}
+
+impl Default for Bunkers {
+    fn default() -> Self {
+        Self::new(Playfield::default())
+    }
+}
+
+impl Drawable for Bunkers {
+    fn draw(&self, frame: &mut Frame) {
+        for cell in self.cells.iter() {
+            frame[cell.x][cell.y] = match cell.health {
+                3 => Cell::new('#').fg(Color::Green),
+                2 => Cell::new('=').fg(Color::DarkGreen),
+                _ => Cell::new('-').fg(Color::DarkYellow)
+            };
+        }
+    }
+}
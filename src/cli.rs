@@ -0,0 +1,212 @@
+use std::iter;
+
+/// the `server` options, listed in both `USAGE` and `SERVER_USAGE`
+macro_rules! server_options {
+    () => {
+        "      --addr <addr>   Address to listen on [default: 127.0.1.0:3000]
+      --data <path>   Score file [default: data/server_scores.json]
+      --allow-unproven Take scores sent without a proof of the game"
+    };
+}
+
+pub const USAGE: &str = concat!("Usage: invaders [OPTIONS] [COMMAND]
+
+Commands:
+  play            Start a game straight away (the default opens the main menu)
+      --level <n>     Level to start on
+      --seed <n>      Seed for the game's randomness
+      --name <name>   Player name, skips the name prompt
+      --no-audio      Don't open an audio device
+      --mute          Start muted, M toggles sound in game
+  leaderboard     Print the leaderboard to stdout and exit
+  replay <file>   Play a recorded game back
+  verify <file>   Check a saved replay the way the score server checks its score
+  server          Run a local score server
+", server_options!(), "
+
+Options:
+  --config <path>       Config file [default: data/config.json]
+  --server-url <url>    Score server to use
+  --offline             Never contact the score server
+  -h, --help            Print this help");
+
+/// Help for the `invaders-server` binary, which takes the same options as `invaders server`.
+pub const SERVER_USAGE: &str = concat!("Usage: invaders-server [OPTIONS]
+
+Serves the leaderboard the game talks to, requests need the API_KEY from the environment or .env
+
+Options:
+", server_options!(), "
+      -h, --help      Print this help");
+
+#[derive(Debug, PartialEq, Default, Clone)]
+pub struct PlayArgs {
+    pub level: Option<u128>,
+    pub seed: Option<u64>,
+    pub name: Option<String>,
+    pub no_audio: bool,
+    pub mute: bool
+}
+
+#[derive(Debug, PartialEq, Default, Clone)]
+pub struct ServerArgs {
+    pub addr: Option<String>,
+    pub data: Option<String>,
+    pub allow_unproven: bool
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum Subcommand {
+    /// main menu, `start` skips straight into a game
+    Play { args: PlayArgs, start: bool },
+    Leaderboard,
+    Replay(String),
+    /// replay file whose score to check the way the score server would
+    Verify(String),
+    Server(ServerArgs),
+    Help
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Cli {
+    pub command: Subcommand,
+    pub config: Option<String>,
+    pub server_url: Option<String>,
+    pub offline: bool
+}
+
+fn value(args: &mut impl Iterator<Item = String>, flag: &str) -> Result<String, String> {
+    args.next().ok_or_else(|| format!("{} Needs A Value", flag))
+}
+
+fn number<T: std::str::FromStr>(args: &mut impl Iterator<Item = String>, flag: &str) -> Result<T, String> {
+    let raw = value(args, flag)?;
+    raw.parse().map_err(|_| format!("Invalid Value For {}: {}", flag, raw))
+}
+
+impl ServerArgs {
+    /**
+     * desc: parses the arguments of the `invaders-server` binary the way `invaders server` does, `None` asks for help
+     */
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Option<ServerArgs>, String> {
+        match Cli::parse(iter::once("server".to_string()).chain(args))?.command {
+            Subcommand::Server(args) => Ok(Some(args)),
+            Subcommand::Help => Ok(None),
+            _ => Err("Only Server Options Apply Here".to_string())
+        }
+    }
+}
+
+/// subcommands the game's own options apply to, no subcommand means `play`
+const GAME: &[&str] = &["play", "leaderboard"];
+const PLAY: &[&str] = &["play"];
+const SERVER: &[&str] = &["server"];
+
+impl Cli {
+    /**
+     * desc: parses the arguments after the program name
+     */
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Cli, String> {
+        let mut args = args.into_iter();
+        let mut play = PlayArgs::default();
+        let mut server = ServerArgs::default();
+        let mut command = None;
+        let mut cli = Cli { command: Subcommand::Help, config: None, server_url: None, offline: false };
+        // every option given and the subcommands it applies to, checked once the subcommand is known
+        let mut options: Vec<(String, &[&str])> = Vec::new();
+        while let Some(arg) = args.next() {
+            let applies_to = match arg.as_str() {
+                "-h" | "--help" => {
+                    command = Some("help".to_string());
+                    None
+                }
+                "--config" => {
+                    cli.config = Some(value(&mut args, &arg)?);
+                    Some(GAME)
+                }
+                "--server-url" => {
+                    cli.server_url = Some(value(&mut args, &arg)?);
+                    Some(GAME)
+                }
+                "--offline" => {
+                    cli.offline = true;
+                    Some(GAME)
+                }
+                "--level" => {
+                    play.level = Some(number(&mut args, &arg)?);
+                    Some(PLAY)
+                }
+                "--seed" => {
+                    play.seed = Some(number(&mut args, &arg)?);
+                    Some(PLAY)
+                }
+                "--name" => {
+                    play.name = Some(value(&mut args, &arg)?);
+                    Some(PLAY)
+                }
+                "--no-audio" => {
+                    play.no_audio = true;
+                    Some(PLAY)
+                }
+                "--mute" => {
+                    play.mute = true;
+                    Some(PLAY)
+                }
+                "--addr" => {
+                    server.addr = Some(value(&mut args, &arg)?);
+                    Some(SERVER)
+                }
+                "--data" => {
+                    server.data = Some(value(&mut args, &arg)?);
+                    Some(SERVER)
+                }
+                "--allow-unproven" => {
+                    server.allow_unproven = true;
+                    Some(SERVER)
+                }
+                // kept from before there were subcommands
+                "--replay" => {
+                    command = Some("replay".to_string());
+                    cli.command = Subcommand::Replay(value(&mut args, &arg)?);
+                    None
+                }
+                "replay" if command.is_none() => {
+                    command = Some(arg.clone());
+                    cli.command = Subcommand::Replay(value(&mut args, &arg)?);
+                    None
+                }
+                "verify" if command.is_none() => {
+                    command = Some(arg.clone());
+                    cli.command = Subcommand::Verify(value(&mut args, &arg)?);
+                    None
+                }
+                "play" | "leaderboard" | "server" if command.is_none() => {
+                    command = Some(arg.clone());
+                    None
+                }
+                _ => return Err(format!("Unknown Argument: {}", arg))
+            };
+            if let Some(applies_to) = applies_to {
+                options.push((arg, applies_to));
+            }
+        }
+        let name = command.as_deref().unwrap_or("play");
+        if name != "help" {
+            if let Some((option, _)) = options.iter().find(|(_, applies_to)| !applies_to.contains(&name)) {
+                return Err(format!("{} Doesn't Apply To {}", option, name));
+            }
+        }
+        if play.level == Some(0) {
+            return Err("Levels Start At 1".to_string());
+        }
+        cli.command = match command.as_deref() {
+            None => Subcommand::Play { args: play, start: false },
+            Some("play") => Subcommand::Play { args: play, start: true },
+            Some("leaderboard") => Subcommand::Leaderboard,
+            Some("server") => Subcommand::Server(server),
+            Some("help") => Subcommand::Help,
+            _ => cli.command
+        };
+        Ok(cli)
+    }
+}
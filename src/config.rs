@@ -0,0 +1,76 @@
+use std::{fs::File, error::Error, io::{Read, ErrorKind}, collections::HashMap};
+use serde::{Serialize, Deserialize};
+
+use crate::{Playfield, audio::Group, invaders::SpeedCurve, request::ClientConfig};
+
+pub const CONFIG_PATH: &str = "data/config.json";
+
+/// Optional settings read from `data/config.json`, anything left out keeps its default.
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct Config {
+    /// playfield size, the terminal size is used when these are left out
+    #[serde(default)]
+    pub cols: Option<usize>,
+    #[serde(default)]
+    pub rows: Option<usize>,
+    /// set to false for monochrome rendering
+    #[serde(default)]
+    pub color: Option<bool>,
+    /// render frame rate cap, 0 for uncapped
+    #[serde(default)]
+    pub fps: Option<u32>,
+    /// score server url, short for `server.url`, `--server-url` takes precedence
+    #[serde(default)]
+    pub server_url: Option<String>,
+    /// score server url, api key, timeout and retries
+    #[serde(default)]
+    pub server: Option<ClientConfig>,
+    /// volume per channel group, e.g. `{"sfx": 0.8, "music": 0.5}`, 1.0 when left out
+    #[serde(default)]
+    pub volume: Option<HashMap<Group, f32>>,
+    /// how the army speeds up as invaders are destroyed, e.g. `{"fastest": 0.1, "exponent": 2.0}`
+    #[serde(default)]
+    pub speed_curve: Option<SpeedCurve>
+}
+
+impl Config {
+    /**
+     * desc: reads the config file, a missing file gives the default config
+     */
+    pub fn load(path: &str) -> Result<Config, Box<dyn Error>> {
+        let mut file = match File::open(path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Config::default()),
+            Err(e) => return Err(Box::new(e)),
+        };
+        let mut contents = String::new();
+        match file.read_to_string(&mut contents) {
+            Ok(data) => data,
+            Err(e) => return Err(Box::new(e))
+        };
+        match serde_json::from_str(&contents) {
+            Ok(config) => Ok(config),
+            Err(e) => Err(format!("Invalid Config File {}: {}", path, e).into())
+        }
+    }
+    /**
+     * desc: true if the config pins the playfield size
+     */
+    pub fn fixed_playfield(&self) -> bool {
+        self.cols.is_some() && self.rows.is_some()
+    }
+    /**
+     * desc: the `server` section with `server_url` applied over it
+     */
+    pub fn client_config(&self) -> ClientConfig {
+        let mut client = self.server.clone().unwrap_or_default();
+        if let Some(url) = &self.server_url {
+            client.url = url.clone();
+        }
+        client
+    }
+    pub fn playfield(&self) -> Playfield {
+        let terminal = Playfield::from_terminal();
+        Playfield::new(self.cols.unwrap_or(terminal.cols), self.rows.unwrap_or(terminal.rows))
+    }
+}
@@ -1,20 +1,116 @@
-use crate::{NUM_COLS, NUM_ROWS};
+use std::{ops::{Index, IndexMut}, sync::mpsc::{self, Sender, Receiver}};
 
-pub type Frame = Vec<Vec<String>>;
+use crossterm::style::{Color, Attribute, Attributes};
 
-pub fn new_frame() -> Frame {
-    let mut cols = Vec::with_capacity(NUM_COLS);
+use crate::Playfield;
 
-    for _ in 0..NUM_COLS {
-        let mut col = Vec::with_capacity(NUM_ROWS);
-        for _ in 0..NUM_ROWS {
-            col.push(" ".to_string());
+/// A single terminal cell: what to print and how to style it.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Cell {
+    pub glyph: char,
+    pub fg: Color,
+    pub bg: Color,
+    pub attributes: Attributes
+}
+
+impl Cell {
+    pub fn new(glyph: char) -> Self {
+        Self { glyph, fg: Color::White, bg: Color::Black, attributes: Attributes::default() }
+    }
+    pub fn fg(mut self, color: Color) -> Self {
+        self.fg = color;
+        self
+    }
+    pub fn bg(mut self, color: Color) -> Self {
+        self.bg = color;
+        self
+    }
+    pub fn attribute(mut self, attribute: Attribute) -> Self {
+        self.attributes.set(attribute);
+        self
+    }
+}
+
+impl Default for Cell {
+    fn default() -> Self {
+        Self::new(' ')
+    }
+}
+
+/// Flat, column major buffer of cells, indexed as `frame[x][y]`.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Frame {
+    field: Playfield,
+    cells: Vec<Cell>
+}
+
+impl Frame {
+    pub fn new(field: Playfield) -> Self {
+        Self { field, cells: vec![Cell::default(); field.cols * field.rows] }
+    }
+    pub fn field(&self) -> Playfield {
+        self.field
+    }
+    pub fn cols(&self) -> usize {
+        self.field.cols
+    }
+    pub fn rows(&self) -> usize {
+        self.field.rows
+    }
+    /**
+     * desc: blanks every cell, resizing first if the playfield changed
+     */
+    pub fn reset(&mut self, field: Playfield) {
+        if field != self.field {
+            self.field = field;
+            self.cells.resize(field.cols * field.rows, Cell::default());
+        }
+        self.cells.fill(Cell::default());
+    }
+}
+
+impl Index<usize> for Frame {
+    type Output = [Cell];
+
+    fn index(&self, x: usize) -> &[Cell] {
+        &self.cells[x * self.field.rows..(x + 1) * self.field.rows]
+    }
+}
+
+impl IndexMut<usize> for Frame {
+    fn index_mut(&mut self, x: usize) -> &mut [Cell] {
+        &mut self.cells[x * self.field.rows..(x + 1) * self.field.rows]
+    }
+}
+
+pub fn new_frame(field: Playfield) -> Frame {
+    Frame::new(field)
+}
+
+/// Hands out blank frames, reusing the ones the render thread sent back.
+pub struct FramePool {
+    recycled: Receiver<Frame>
+}
+
+impl FramePool {
+    /**
+     * desc: the pool and the sender the render thread returns finished frames through
+     */
+    pub fn new() -> (Self, Sender<Frame>) {
+        let (tx, rx) = mpsc::channel();
+        (Self { recycled: rx }, tx)
+    }
+    pub fn next(&self, field: Playfield) -> Frame {
+        match self.recycled.try_recv() {
+            Ok(mut frame) => {
+                frame.reset(field);
+                frame
+            }
+            Err(_) => Frame::new(field)
         }
-        cols.push(col);
     }
-    cols
 }
 
 pub trait Drawable {
     fn draw(&self, frame: &mut Frame);
-}
\ No newline at end of file
+}
@@ -0,0 +1,177 @@
+use std::time::Duration;
+use serde::{Serialize, Deserialize};
+
+use crate::{Playfield, player::Player, invaders::{Invaders, SpeedCurve}, frame::{Drawable, Frame}, menu::NewMenu, rng::Rng, bunkers::Bunkers, ufo::Ufo, level::Level};
+
+/// Abstract input understood by the game, independent of any terminal or key mapping.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Copy)]
+pub enum Command {
+    MoveLeft,
+    MoveRight,
+    Shoot
+}
+
+/// A command and the tick it was applied on, enough to replay a game from its seed.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Copy)]
+pub struct Input {
+    pub tick: u64,
+    pub command: Command
+}
+
+/// Things that happened during a single `Game::update`, for front ends to react to (audio, UI).
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum GameEvent {
+    Shot,
+    InvadersMoved,
+    Hit,
+    Bomb,
+    UfoSpawned,
+    UfoHit(u32),
+    PlayerHit,
+    LevelCleared(u128),
+    GameOver
+}
+
+pub struct Game {
+    pub player: Player,
+    pub invaders: Invaders,
+    pub bunkers: Bunkers,
+    pub ufo: Ufo,
+    pub over: bool,
+    pub seed: u64,
+    pub levels: Vec<Level>,
+    pub field: Playfield,
+    /// level the game started on
+    pub start_level: u128,
+    /// number of updates so far
+    pub tick: u64,
+    /// every command applied so far
+    pub inputs: Vec<Input>,
+    /// how the army speeds up as it shrinks, on every level
+    pub speed_curve: SpeedCurve,
+    rng: Rng
+}
+
+impl Game {
+    pub fn new() -> Self {
+        Self::with_seed(Rng::seed_from_time())
+    }
+    pub fn with_seed(seed: u64) -> Self {
+        Self::with_levels(seed, Vec::new(), Playfield::default())
+    }
+    /**
+     * desc: plays through `levels` first, then generates the following ones
+     */
+    pub fn with_levels(seed: u64, levels: Vec<Level>, field: Playfield) -> Self {
+        let mut rng = Rng::new(seed);
+        let ufo = Ufo::new(&mut rng, field);
+        let speed_curve = SpeedCurve::default();
+        let (invaders, bunkers) = Self::build_level(&levels, 1, field, speed_curve);
+        Self { player: Player::new(field), invaders, bunkers, ufo, over: false, seed, levels, field, start_level: 1, tick: 0, inputs: Vec::new(), speed_curve, rng }
+    }
+    /**
+     * desc: changes how the army speeds up, from the current level on
+     */
+    pub fn set_speed_curve(&mut self, curve: SpeedCurve) {
+        self.speed_curve = curve;
+        self.invaders.curve = curve;
+    }
+    /**
+     * desc: skips ahead to `level` before the game starts
+     */
+    pub fn start_at(&mut self, level: u128) {
+        let level = level.max(1);
+        self.start_level = level;
+        self.player.level = level;
+        (self.invaders, self.bunkers) = Self::build_level(&self.levels, level, self.field, self.speed_curve);
+    }
+    fn build_level(levels: &[Level], level: u128, field: Playfield, curve: SpeedCurve) -> (Invaders, Bunkers) {
+        let (mut invaders, bunkers) = match levels.get(level as usize - 1) {
+            Some(def) => (Invaders::from_level(def, field), Bunkers::from_level(def, field)),
+            // generated levels pick up from the real level number so they never get easier than the files before them
+            None => (Invaders::new(level, field), Bunkers::new(field))
+        };
+        invaders.curve = curve;
+        (invaders, bunkers)
+    }
+    /**
+     * desc: advances the game by `delta` after applying `commands` in order,
+     * front ends call it with a fixed `timestep::TICK` so runs are reproducible
+     */
+    pub fn update(&mut self, commands: &[Command], delta: Duration) -> Vec<GameEvent> {
+        let mut events = Vec::new();
+        if self.over {
+            return events;
+        }
+        self.tick += 1;
+        for command in commands {
+            self.inputs.push(Input { tick: self.tick, command: *command });
+            match command {
+                Command::MoveLeft => self.player.move_left(),
+                Command::MoveRight => self.player.move_right(),
+                Command::Shoot => {
+                    if self.player.shoot() {
+                        events.push(GameEvent::Shot);
+                    }
+                }
+            }
+        }
+        self.player.update(delta);
+        if self.invaders.update(delta) {
+            self.bunkers.crush(&self.invaders);
+            events.push(GameEvent::InvadersMoved);
+        }
+        if self.invaders.update_shots(delta, &mut self.rng) {
+            events.push(GameEvent::Bomb);
+        }
+        if self.ufo.update(delta, &mut self.rng) {
+            events.push(GameEvent::UfoSpawned);
+        }
+        if let Some(bonus) = self.player.detect_ufo_hit(&mut self.ufo, &mut self.rng) {
+            events.push(GameEvent::UfoHit(bonus));
+        }
+        self.invaders.detect_bunker_hits(&mut self.bunkers);
+        if self.player.detect_hits(&mut self.invaders, &mut self.bunkers) {
+            events.push(GameEvent::Hit);
+        }
+        if self.player.detect_hit(&mut self.invaders) {
+            events.push(GameEvent::PlayerHit);
+            if !self.player.lose_life() {
+                self.over = true;
+                events.push(GameEvent::GameOver);
+                return events;
+            }
+        }
+
+        // win lose conditions
+        if self.invaders.all_dead() {
+            self.player.level += 1;
+            (self.invaders, self.bunkers) = Self::build_level(&self.levels, self.player.level, self.field, self.speed_curve);
+            self.player.clear_shots();
+            events.push(GameEvent::LevelCleared(self.player.level));
+        }
+        if self.invaders.reached_bottom() {
+            self.over = true;
+            events.push(GameEvent::GameOver);
+        }
+        events
+    }
+}
+
+impl Default for Game {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drawable for Game {
+    fn draw(&self, frame: &mut Frame) {
+        self.bunkers.draw(frame);
+        self.invaders.draw(frame);
+        self.player.draw(frame);
+        let score_str = format!("Level: {}, Score: {}, Lives: {}", self.player.level, self.player.score, self.player.lives);
+        NewMenu::new(score_str, 2, 0).draw(frame);
+        // the ufo flies over the hud
+        self.ufo.draw(frame);
+    }
+}
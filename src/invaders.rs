@@ -1,49 +1,214 @@
 use std::time::Duration;
 
+use crossterm::style::Color;
 use rusty_time::timer::Timer;
+use serde::{Serialize, Deserialize};
 
-use crate::{Direction, NUM_COLS, NUM_ROWS, frame::Drawable, INVADER_MULTIPLIER};
+use crate::{Direction, Playfield, frame::{Drawable, Cell}, shot::{Shot, ShotDirection}, rng::Rng, bunkers::Bunkers, level::Level};
+
+/// milliseconds between army steps on the first level
+pub const MOVE_INTERVAL: u64 = 2000;
+/// milliseconds between invader bombs on the first level
+pub const FIRE_INTERVAL: u64 = 1500;
+
+/// How the army's move interval shrinks as invaders are destroyed, the interval is
+/// `level interval * (fastest + (1 - fastest) * (remaining / starting army) ^ exponent)`.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(default)]
+pub struct SpeedCurve {
+    /// fraction of the level's interval left once the army is almost gone
+    pub fastest: f64,
+    /// above 1 keeps the army slow for longer and speeds it up sharply at the end
+    pub exponent: f64
+}
+
+impl SpeedCurve {
+    /**
+     * desc: the army keeps the same pace however many are left
+     */
+    pub fn constant() -> Self {
+        Self { fastest: 1.0, exponent: 1.0 }
+    }
+    /**
+     * desc: move interval for an army that started `full` strong with `remaining` left
+     */
+    pub fn interval(&self, base: Duration, remaining: usize, full: usize) -> Duration {
+        if full == 0 {
+            return base;
+        }
+        let fastest = self.fastest.clamp(0.0, 1.0);
+        let left = (remaining.min(full) as f64 / full as f64).powf(self.exponent.max(0.0));
+        base.mul_f64(fastest + (1.0 - fastest) * left).max(Duration::from_millis(1))
+    }
+}
+
+impl Default for SpeedCurve {
+    fn default() -> Self {
+        Self { fastest: 0.05, exponent: 1.5 }
+    }
+}
+
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum InvaderKind {
+    Squid,
+    Crab,
+    Octopus
+}
+
+impl InvaderKind {
+    /**
+     * desc: kind by row band of a formation `rows` high, squids on top and octopuses at the front
+     */
+    pub fn for_row(y: usize, rows: usize) -> Self {
+        match y * 3 / rows.max(1) {
+            0 => InvaderKind::Squid,
+            1 => InvaderKind::Crab,
+            _ => InvaderKind::Octopus
+        }
+    }
+    /**
+     * desc: the two animation frames
+     */
+    pub fn glyphs(&self) -> (char, char) {
+        match self {
+            InvaderKind::Squid => ('x', '+'),
+            InvaderKind::Crab => ('M', 'W'),
+            InvaderKind::Octopus => ('O', '0')
+        }
+    }
+    pub fn color(&self) -> Color {
+        match self {
+            InvaderKind::Squid => Color::Magenta,
+            InvaderKind::Crab => Color::Cyan,
+            InvaderKind::Octopus => Color::Green
+        }
+    }
+    pub fn points(&self) -> u32 {
+        match self {
+            InvaderKind::Squid => 30,
+            InvaderKind::Crab => 20,
+            InvaderKind::Octopus => 10
+        }
+    }
+    pub fn health(&self) -> u8 {
+        match self {
+            InvaderKind::Squid => 2,
+            InvaderKind::Crab | InvaderKind::Octopus => 1
+        }
+    }
+}
 
 pub struct Invader {
     pub x: usize,
-    pub y: usize
+    pub y: usize,
+    pub kind: InvaderKind,
+    pub health: u8
 }
 
 impl Invader {
-    pub fn new(x: usize, y: usize) -> Self {
-        Self {x, y}
+    pub fn new(x: usize, y: usize, kind: InvaderKind) -> Self {
+        Self {x, y, kind, health: kind.health()}
     }
 }
 
 pub struct Invaders {
     pub army: Vec<Invader>,
     move_timer: Timer,
+    /// move interval with the whole army alive
+    base_interval: Duration,
+    /// size of the army at the start of the level
+    full: usize,
+    pub curve: SpeedCurve,
     direction: Direction,
-    pub speed: f64
+    pub speed: f64,
+    pub shots: Vec<Shot>,
+    field: Playfield,
+    fire_timer: Timer
 }
 
 impl Invaders {
-    pub fn new(speed: u128) -> Self {
+    pub fn new(speed: u128, field: Playfield) -> Self {
         let mut army = Vec::new();
-        for x in 0..NUM_COLS {
-            for y in 0..NUM_ROWS {
+        let formation_rows = field.formation_rows();
+        for x in 0..field.cols {
+            for y in 0..field.rows {
                 if (x % 2 == 0) && (y % 2 == 0) &&
-                   (x > 1) && (x < NUM_COLS - 2) &&
-                   (y > 0) && (y < INVADER_MULTIPLIER as usize) {
-                    army.push(Invader::new(x, y));
+                   (x > 1) && (x < field.cols - 2) &&
+                   (y > 0) && (y < formation_rows) {
+                    army.push(Invader::new(x, y, InvaderKind::for_row(y, formation_rows)));
                 }
             }
         }
         let multiplier = speed as f64 * 1.05;
         let move_timer = if speed == 1 {
-            Timer::from_millis(2000)
+            Timer::from_millis(MOVE_INTERVAL)
         }
         else {
-            Timer::from_millis((2000.0 / multiplier) as u64)
+            Timer::from_millis((MOVE_INTERVAL as f64 / multiplier) as u64)
         };
-        Self { army, move_timer, direction: Direction::Left, speed: multiplier }
+        let fire_timer = if speed == 1 {
+            Timer::from_millis(FIRE_INTERVAL)
+        }
+        else {
+            Timer::from_millis((FIRE_INTERVAL as f64 / multiplier) as u64)
+        };
+        let full = army.len();
+        Self {
+            army,
+            base_interval: move_timer.duration,
+            move_timer,
+            full,
+            curve: SpeedCurve::default(),
+            direction: Direction::Left,
+            speed: multiplier,
+            shots: Vec::new(),
+            field,
+            fire_timer
+        }
+    }
+    /**
+     * desc: army from a level file, invaders outside the playfield are left out
+     */
+    pub fn from_level(level: &Level, field: Playfield) -> Self {
+        let army: Vec<Invader> = level.invaders().into_iter()
+            .filter(|(x, y, _)| *x < field.cols && *y < field.rows - 1)
+            .map(|(x, y, kind)| Invader::new(x, y, kind))
+            .collect();
+        let move_interval = level.move_interval.max(1);
+        Self {
+            full: army.len(),
+            army,
+            move_timer: Timer::from_millis(move_interval),
+            base_interval: Duration::from_millis(move_interval),
+            curve: SpeedCurve::default(),
+            direction: Direction::Left,
+            speed: MOVE_INTERVAL as f64 / move_interval as f64,
+            shots: Vec::new(),
+            field,
+            fire_timer: Timer::from_millis(level.fire_interval.max(1))
+        }
+    }
+    /**
+     * desc: time between army steps, shrinking along `curve` as the army is destroyed
+     */
+    pub fn move_interval(&self) -> Duration {
+        self.curve.interval(self.base_interval, self.army.len(), self.full)
+    }
+    /**
+     * desc: time between notes of the march, the move interval shortened by up to half as the army descends
+     */
+    pub fn beat(&self) -> Duration {
+        let lowest = self.army.iter().map(|invader| invader.y).max().unwrap_or(0);
+        let depth = lowest as f64 / self.field.rows.max(1) as f64;
+        self.move_interval().mul_f64(1.0 - depth / 2.0)
     }
     pub fn update(&mut self, delta: Duration) -> bool {
+        let interval = self.move_interval();
+        if interval != self.move_timer.duration {
+            // a faster army doesn't wait out the rest of the slower step
+            self.move_timer.duration = interval;
+            self.move_timer.time_left = self.move_timer.time_left.min(interval);
+        }
         self.move_timer.update(delta);
         if !self.move_timer.ready {
             return false;
@@ -59,7 +224,7 @@ impl Invaders {
         }
         if self.direction == Direction::Right {
             let max_x = self.army.iter().map(|invader| invader.x).max().unwrap_or(0);
-            if max_x == NUM_COLS - 1 {
+            if max_x == self.field.cols - 1 {
                 self.direction = Direction::Left;
                 downwards = true;
             }
@@ -67,20 +232,56 @@ impl Invaders {
         if downwards {
             for invader in self.army.iter_mut() {
                 invader.y = match invader.y + 1 {
-                    y if y >= NUM_ROWS => y - 1,
+                    y if y >= self.field.rows => y - 1,
                     y => y
                 };
             }
         } else {
             for invader in self.army.iter_mut() {
                 invader.x = match ((invader.x as i32) + self.direction.clone() as i32) as usize {
-                    x if x >= NUM_COLS => (x as i32 - self.direction.clone() as i32) as usize,
+                    x if x >= self.field.cols => (x as i32 - self.direction.clone() as i32) as usize,
                     x => x
                 }
             }
         }
         true
     }
+    /**
+     * desc: moves the bombs and lets a random front line invader drop a new one, returns true when one was dropped
+     */
+    pub fn update_shots(&mut self, delta: Duration, rng: &mut Rng) -> bool {
+        for shot in self.shots.iter_mut() {
+            shot.update(delta);
+        }
+        self.shots.retain(|shot| !shot.dead());
+        self.fire_timer.update(delta);
+        if !self.fire_timer.ready || self.army.is_empty() {
+            return false;
+        }
+        self.fire_timer.reset();
+        // only the lowest invader of a column can fire
+        let shooter = &self.army[rng.range(0, self.army.len() as u64) as usize];
+        let y = self.army.iter()
+            .filter(|invader| invader.x == shooter.x)
+            .map(|invader| invader.y)
+            .max()
+            .unwrap_or(shooter.y);
+        if y + 1 >= self.field.rows {
+            return false;
+        }
+        self.shots.push(Shot::new(shooter.x, y + 1, ShotDirection::Down, self.field));
+        true
+    }
+    /**
+     * desc: lets the bunkers absorb any bombs that reached them
+     */
+    pub fn detect_bunker_hits(&mut self, bunkers: &mut Bunkers) -> bool {
+        let mut hit = false;
+        for shot in self.shots.iter_mut() {
+            hit |= bunkers.absorb(shot);
+        }
+        hit
+    }
     /**
      * desc: win condition
      */
@@ -91,29 +292,38 @@ impl Invaders {
      * desc: lose condition
      */
     pub fn reached_bottom(&self) -> bool {
-        self.army.iter().map(|invader| invader.y).max().unwrap_or(0) >= NUM_ROWS - 1
+        self.army.iter().map(|invader| invader.y).max().unwrap_or(0) >= self.field.rows - 1
     }
-    pub fn kill_at(&mut self, x: usize, y: usize) -> bool {
+    /**
+     * desc: damages the invader at x, y, returns the points scored (0 if it survived)
+     */
+    pub fn hit_at(&mut self, x: usize, y: usize) -> Option<u32> {
         for (i, invader) in self.army.iter_mut().enumerate() {
             if invader.x == x && invader.y == y {
+                invader.health -= 1;
+                if invader.health > 0 {
+                    return Some(0);
+                }
+                let points = invader.kind.points();
                 self.army.remove(i);
-                return true
+                return Some(points)
             }
         }
-        false
+        None
     }
 }
 
 impl Drawable for Invaders {
     fn draw(&self, frame: &mut crate::frame::Frame) {
+        let first_frame = self.move_timer.time_left.as_secs_f32() /
+            self.move_timer.duration.as_secs_f32() > 0.5;
         for invader in self.army.iter() {
-            frame[invader.x][invader.y] = 
-                if self.move_timer.time_left.as_secs_f32() /
-                self.move_timer.duration.as_secs_f32() > 0.5 {
-                "x".to_string()
-            } else {
-                "+".to_string()
-            }
+            let (a, b) = invader.kind.glyphs();
+            let glyph = if first_frame { a } else { b };
+            frame[invader.x][invader.y] = Cell::new(glyph).fg(invader.kind.color());
+        }
+        for shot in self.shots.iter() {
+            shot.draw(frame);
         }
     }
 }
\ No newline at end of file
@@ -0,0 +1,84 @@
+use std::{fs::{self, File}, error::Error, io::{ErrorKind, Read}, path::Path};
+use serde::{Serialize, Deserialize};
+
+use crate::invaders::InvaderKind;
+
+pub const LEVEL_DIR: &str = "data/levels";
+
+/// A hand made level, loaded from a json file in `data/levels/`.
+///
+/// `formation` is an ascii grid starting at row 1 (row 0 belongs to the hud and ufo),
+/// where `s`, `c` and `o` place a squid, crab or octopus. `bunkers` is a grid whose last
+/// line sits on the usual bunker row, `#` being a bunker cell; leave it out for the default
+/// bunkers or pass an empty list for none.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Level {
+    #[serde(default)]
+    pub name: String,
+    pub formation: Vec<String>,
+    /// milliseconds between army steps
+    pub move_interval: u64,
+    /// milliseconds between invader bombs
+    pub fire_interval: u64,
+    #[serde(default)]
+    pub bunkers: Option<Vec<String>>
+}
+
+impl Level {
+    pub fn load(path: &Path) -> Result<Level, Box<dyn Error>> {
+        let mut file = match File::open(path) {
+            Ok(file) => file,
+            Err(e) => return Err(Box::new(e)),
+        };
+        let mut contents = String::new();
+        match file.read_to_string(&mut contents) {
+            Ok(data) => data,
+            Err(e) => return Err(Box::new(e))
+        };
+        match serde_json::from_str(&contents) {
+            Ok(level) => Ok(level),
+            Err(e) => Err(format!("Invalid Level File {}: {}", path.display(), e).into())
+        }
+    }
+    /**
+     * desc: loads every `.json` level in `dir`, ordered by file name, a missing `dir` gives no levels
+     */
+    pub fn load_dir(dir: &str) -> Result<Vec<Level>, Box<dyn Error>> {
+        let entries = match fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(Box::new(e))
+        };
+        let mut paths = Vec::new();
+        for entry in entries {
+            let path = entry?.path();
+            if path.extension().is_some_and(|ext| ext == "json") {
+                paths.push(path);
+            }
+        }
+        paths.sort();
+        let mut levels = Vec::new();
+        for path in paths {
+            levels.push(Level::load(&path)?);
+        }
+        Ok(levels)
+    }
+    /**
+     * desc: invader positions and kinds from the formation grid
+     */
+    pub fn invaders(&self) -> Vec<(usize, usize, InvaderKind)> {
+        let mut cells = Vec::new();
+        for (row, line) in self.formation.iter().enumerate() {
+            for (x, c) in line.chars().enumerate() {
+                let kind = match c {
+                    's' | 'S' => InvaderKind::Squid,
+                    'c' | 'C' => InvaderKind::Crab,
+                    'o' | 'O' => InvaderKind::Octopus,
+                    _ => continue
+                };
+                cells.push((x, row + 1, kind));
+            }
+        }
+        cells
+    }
+}
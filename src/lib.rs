@@ -6,14 +6,82 @@ pub mod invaders;
 pub mod system;
 pub mod menu;
 pub mod request;
+pub mod game;
+pub mod rng;
+pub mod bunkers;
+pub mod ufo;
+pub mod level;
+pub mod config;
+pub mod timestep;
+pub mod replay;
+pub mod cli;
+pub mod server;
+pub mod audio;
+pub mod scores;
+pub mod proof;
 
+use serde::{Serialize, Deserialize};
+
+/// default playfield size
 pub const NUM_ROWS: usize = 20;
 pub const NUM_COLS: usize = 40;
+/// smallest playfield the game and menus still fit in
+pub const MIN_ROWS: usize = 16;
+pub const MIN_COLS: usize = 36;
+/// largest playfield taken from the terminal size
+pub const MAX_ROWS: usize = 30;
+pub const MAX_COLS: usize = 80;
 pub const INVADER_MULTIPLIER: u32 = 9;
 pub const SHOT_COUNT: usize = 6;
+pub const PLAYER_LIVES: u32 = 3;
+/// milliseconds the player can't be hit after respawning
+pub const RESPAWN_MILLIS: u64 = 2000;
 
 #[derive(PartialEq, Clone)]
 pub enum Direction {
     Left = -1,
     Right = 1
-}
\ No newline at end of file
+}
+
+/// Size of the playfield in terminal cells, decided at runtime.
+#[derive(PartialEq, Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct Playfield {
+    pub cols: usize,
+    pub rows: usize
+}
+
+impl Playfield {
+    pub fn new(cols: usize, rows: usize) -> Self {
+        Self { cols: cols.max(MIN_COLS), rows: rows.max(MIN_ROWS) }
+    }
+    /**
+     * desc: playfield filling the terminal, capped to `MAX_COLS` x `MAX_ROWS`
+     */
+    pub fn from_terminal() -> Self {
+        match crossterm::terminal::size() {
+            Ok((cols, rows)) => Self::new((cols as usize).min(MAX_COLS), (rows as usize).min(MAX_ROWS)),
+            Err(_) => Self::default()
+        }
+    }
+    /**
+     * desc: true if the terminal is at least as big as the playfield
+     */
+    pub fn fits_terminal(&self) -> bool {
+        match crossterm::terminal::size() {
+            Ok((cols, rows)) => cols as usize >= self.cols && rows as usize >= self.rows,
+            Err(_) => true
+        }
+    }
+    /**
+     * desc: rows the procedural formation may fill, scaled from `INVADER_MULTIPLIER`
+     */
+    pub fn formation_rows(&self) -> usize {
+        self.rows * INVADER_MULTIPLIER as usize / NUM_ROWS
+    }
+}
+
+impl Default for Playfield {
+    fn default() -> Self {
+        Self { cols: NUM_COLS, rows: NUM_ROWS }
+    }
+}
@@ -1,29 +1,315 @@
-use std::{error::Error, time::{Duration, Instant}, sync::{mpsc}, thread};
-use invaders::{render::{render}, frame::{self, Drawable, Frame}, player::Player, invaders::Invaders, system::{System, SystemPlayer, Audio}, menu::{Menu, NewMenu}, request::{ReqClient}};
-use rodio::OutputStream;
+use std::{error::Error, time::{Duration, Instant}, sync::{mpsc, Arc, atomic::{AtomicUsize, Ordering}}, thread, mem, env, process};
+use tokio::task::JoinHandle;
+use invaders::{render::{self, render, render_too_small}, frame::{self, Drawable, Frame, FramePool}, game::{Game, Command, GameEvent}, system::{System, SystemPlayer, Period, RankedScore, ScorePage}, scores::{self, ScoreStore, LocalScore, SCORES_PATH, format_date}, audio::{self, Audio, Group, Track}, menu::{Menu, NewMenu}, request::{ReqClient, LeaderboardError}, level::{Level, LEVEL_DIR}, rng::Rng, config::{Config, CONFIG_PATH}, timestep::{FixedTimestep, FrameLimiter, TICK, DEFAULT_FPS}, replay::{Replay, Playback, REPLAY_DIR}, cli::{Cli, Subcommand, USAGE}, server::{self, SERVER_ADDR, SERVER_DATA}, proof::{self, ScoreProof}, invaders::SpeedCurve};
 use std::io;
-use crossterm::{terminal::{self, LeaveAlternateScreen}, ExecutableCommand, cursor::{Hide, Show}, event::{self, Event, KeyCode}};
+use crossterm::{style::Color, terminal::{self, LeaveAlternateScreen}, ExecutableCommand, cursor::{Hide, Show}, event::{self, Event, KeyCode}};
 use crossterm::terminal::EnterAlternateScreen;
 
+/**
+ * desc: plays a recorded game back in real time, Esc stops it early, then says whether
+ * it scores what was recorded with the level files here and the default speed curve
+ */
+fn play_replay(path: &str) -> Result<(), Box<dyn Error>> {
+    let replay = Replay::load(path)?;
+    let levels = Level::load_dir(LEVEL_DIR)?;
+    let mut game = replay.new_game();
+    let mut playback = Playback::new(&replay);
+
+    let mut stdout = io::stdout();
+    terminal::enable_raw_mode()?;
+    stdout.execute(EnterAlternateScreen)?;
+    stdout.execute(Hide)?;
+
+    let mut last_frame = frame::new_frame(replay.field);
+    render(&mut stdout, &last_frame, &last_frame, true);
+    let mut timestep = FixedTimestep::new();
+    'replay: while !playback.finished(&game) {
+        while event::poll(Duration::default())? {
+            if let Event::Key(key_event) = event::read()? {
+                if key_event.code == KeyCode::Esc {
+                    break 'replay;
+                }
+            }
+        }
+        for _ in 0..timestep.advance() {
+            if !playback.finished(&game) {
+                playback.step(&mut game);
+            }
+        }
+        let mut curr_frame = frame::new_frame(replay.field);
+        game.draw(&mut curr_frame);
+        render(&mut stdout, &last_frame, &curr_frame, false);
+        last_frame = curr_frame;
+        thread::sleep(Duration::from_millis(1));
+    }
+
+    stdout.execute(Show)?;
+    stdout.execute(LeaveAlternateScreen)?;
+    terminal::disable_raw_mode()?;
+    if playback.finished(&game) {
+        let verdict = if replay.verify(&levels, SpeedCurve::default()) {
+            "matches the recording"
+        } else {
+            "does NOT match the recording"
+        };
+        println!("Replay of {}: level {}, score {} ({} level {}, score {})",
+            replay.name, game.player.level, game.player.score, verdict, replay.level, replay.score);
+    }
+    Ok(())
+}
+
+/**
+ * desc: checks the score of a replay saved by the game the way the score server would, with the level
+ * files here and the default speed curve, exits with 1 when it's rejected
+ */
+fn verify_submission(path: &str) -> Result<(), Box<dyn Error>> {
+    // a saved replay holds everything the proof sent with its score is made from
+    let replay = Replay::load(path)?;
+    let proof = ScoreProof::from_replay(&replay);
+    let player = SystemPlayer { name: replay.name, score: replay.score, is: "score".to_string(), proof: Some(proof) };
+    if let Err(e) = proof::verify(&player, &Level::load_dir(LEVEL_DIR)?, SpeedCurve::default()) {
+        println!("Rejected: {}", e);
+        process::exit(1);
+    }
+    // a verified score always has a proof
+    if let Some(proof) = &player.proof {
+        let secs = proof.duration().as_secs();
+        println!("Valid: {} scored {} reaching level {} in {}m {:02}s", player.name, player.score, proof.level, secs / 60, secs % 60);
+    }
+    Ok(())
+}
+
+/**
+ * desc: shows why there is no sound in the top right corner
+ */
+fn draw_audio_status(audio: &dyn Audio, frame: &mut Frame) {
+    if let Some(status) = audio.status() {
+        let x = frame.cols().saturating_sub(status.len() + 1);
+        NewMenu::new(status.to_string(), x, 0).color(Color::DarkGrey).draw(frame);
+    }
+}
+
+/// How far a sync of the queued scores got, kept outside the task sending them so a sync
+/// that's abandoned part way still takes the scores it sent off the queue.
+#[derive(Default)]
+struct SyncProgress {
+    /// queued scores the server answered, taken or refused
+    done: AtomicUsize,
+    refused: AtomicUsize
+}
+
+/**
+ * desc: sends the scores queued while the server was unreachable, stops at the first one that still can't be sent.
+ * A score the server refuses would only be refused again, it counts as done so it's dropped from the queue
+ */
+async fn send_pending(client: &ReqClient, pending: Vec<LocalScore>, progress: &SyncProgress) {
+    for score in pending {
+        match client.update_scores(score.player()).await {
+            Err(e) if e.should_queue() => break,
+            Err(_) => {
+                progress.refused.fetch_add(1, Ordering::SeqCst);
+            }
+            Ok(_) => {}
+        }
+        progress.done.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+/**
+ * desc: takes the scores a sync got through off the queue, returns how many of them the server refused
+ */
+fn finish_sync(store: &mut ScoreStore, progress: &SyncProgress) -> Result<usize, Box<dyn Error>> {
+    let done = progress.done.load(Ordering::SeqCst);
+    if done > 0 {
+        store.synced(done)?;
+    }
+    Ok(progress.refused.load(Ordering::SeqCst))
+}
+
+/**
+ * desc: sends the queued scores and takes them off the queue, returns how many the server refused
+ */
+async fn sync_scores(client: &ReqClient, store: &mut ScoreStore) -> Result<usize, Box<dyn Error>> {
+    let progress = SyncProgress::default();
+    send_pending(client, store.pending.clone(), &progress).await;
+    finish_sync(store, &progress)
+}
+
+/**
+ * desc: tells the player queued scores were dropped because the server refused them
+ */
+fn refused_text(refused: usize) -> String {
+    match refused {
+        1 => "1 Queued Score Refused By The Server".to_string(),
+        n => format!("{} Queued Scores Refused By The Server", n)
+    }
+}
+
+/**
+ * desc: one line per local score, best first
+ */
+fn local_leaderboard(store: &ScoreStore) -> Vec<String> {
+    store.scores.iter().enumerate().map(|(i, s)| {
+        // scores from older files don't know their level or date
+        let mut details = Vec::new();
+        if s.level > 0 {
+            details.push(format!("Level {}", s.level));
+        }
+        if s.date > 0 {
+            details.push(format_date(s.date));
+        }
+        let details = if details.is_empty() { String::new() } else { format!(" ({})", details.join(", ")) };
+        format!("{}: {} - {}{}", i + 1, s.name, s.score, details)
+    }).collect()
+}
+
+type PageTask = JoinHandle<Result<ScorePage, LeaderboardError>>;
+
+/**
+ * desc: asks the server for a page in the background so the menu keeps drawing and Esc works while it loads,
+ * the queued scores in `sync` are sent first, see `finish_sync` for taking them off the queue
+ */
+fn load_page(client: &ReqClient, sync: Option<(Vec<LocalScore>, Arc<SyncProgress>)>, period: Period, offset: usize, limit: usize, around: Option<String>) -> PageTask {
+    let client = client.clone();
+    tokio::spawn(async move {
+        if let Some((pending, progress)) = sync {
+            send_pending(&client, pending, &progress).await;
+        }
+        client.get_page(period, offset, limit, around.as_deref()).await
+    })
+}
+
+/**
+ * desc: one leaderboard line, the column header when `score` is `None`
+ */
+fn leaderboard_line(score: Option<&RankedScore>) -> String {
+    match score {
+        Some(s) => {
+            let date = if s.date > 0 { format_date(s.date) } else { String::new() };
+            let name: String = s.name.chars().take(16).collect();
+            format!("{:>5}  {:<16} {:>8}  {:<10}", s.rank, name, s.score, date)
+        }
+        None => format!("{:>5}  {:<16} {:>8}  {:<10}", "Rank", "Name", "Score", "Date")
+    }
+}
+
+/**
+ * desc: frame of a spinner for something that began at `started`
+ */
+fn spinner(started: Instant) -> char {
+    ['|', '/', '-', '\\'][(started.elapsed().as_millis() / 100 % 4) as usize]
+}
+
+/**
+ * desc: prints the leaderboard for scripts and headless machines,
+ * the local one when offline or the server can't be reached
+ */
+async fn print_leaderboard(client: &ReqClient, store: &mut ScoreStore, offline: bool) -> Result<(), Box<dyn Error>> {
+    let scores = if offline {
+        Err("Offline".to_string())
+    } else {
+        let refused = sync_scores(client, store).await?;
+        if refused > 0 {
+            println!("{}", refused_text(refused));
+        }
+        client.get_scores().await.map_err(|e| e.to_string())
+    };
+    match scores {
+        Ok(scores) => {
+            for (i, p) in scores.iter().enumerate() {
+                println!("{}: {} With a Score of {}", i + 1, p.name, p.score);
+            }
+        }
+        Err(e) => {
+            println!("Local Leaderboard ({})", e);
+            for line in local_leaderboard(store) {
+                println!("{}", line);
+            }
+        }
+    }
+    Ok(())
+}
+
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn Error>> {
     dotenv::dotenv().ok();
-    // initialize audio
-    let (_stream, stream_handle) = OutputStream::try_default().unwrap();
-    let mut audio = Audio::new(stream_handle);
-    audio.add("explosion", "audio/explosion.wav");
-    audio.add("lose", "audio/lose.wav");
-    audio.add("move", "audio/move.wav");
-    audio.add("pew", "audio/pew.wav");
-    audio.add("startup", "audio/startup.wav");
-    audio.add("win", "audio/win.wav");
+    let cli = match Cli::parse(env::args().skip(1)) {
+        Ok(cli) => cli,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, USAGE);
+            process::exit(2);
+        }
+    };
+    let config_path = cli.config.as_deref().unwrap_or(CONFIG_PATH);
+
+    // commands that don't touch the game's config or scores run before either is loaded
+    let play = match cli.command {
+        Subcommand::Help => {
+            println!("{}", USAGE);
+            return Ok(());
+        }
+        Subcommand::Replay(path) => return play_replay(&path),
+        Subcommand::Verify(path) => return verify_submission(&path),
+        Subcommand::Server(args) => {
+            let addr = args.addr.unwrap_or_else(|| SERVER_ADDR.to_string());
+            let data = args.data.unwrap_or_else(|| SERVER_DATA.to_string());
+            return server::run(&addr, &data, env::var("API_KEY").unwrap_or_default(), args.allow_unproven, Level::load_dir(LEVEL_DIR)?).await;
+        }
+        Subcommand::Leaderboard => None,
+        Subcommand::Play { args, start } => Some((args, start))
+    };
+    let config = Config::load(config_path)?;
 
     // init reqwest client
-    let client = ReqClient::new();
+    let mut client_config = config.client_config();
+    if let Some(url) = cli.server_url.clone() {
+        client_config.url = url;
+    }
+    let client = ReqClient::from_config(client_config);
 
     // init system
     let mut system = System::new();
-    
+    system.scores = ScoreStore::load(SCORES_PATH)?;
+
+    let play_args = match play {
+        None => return print_leaderboard(&client, &mut system.scores, cli.offline).await,
+        Some((args, start)) => {
+            if start {
+                system.menu = Menu::Game;
+            }
+            args
+        }
+    };
+
+    // initialize audio, falls back to silence without a sound device
+    let mut audio = audio::open(play_args.no_audio, play_args.mute);
+    // name, file, channel group and priority, a louder event can take over a quieter one's voice
+    let sounds = [
+        ("explosion", "audio/explosion.wav", Group::Sfx, 3),
+        ("lose", "audio/lose.wav", Group::Sfx, 4),
+        ("move", "audio/move.wav", Group::Sfx, 0),
+        ("pew", "audio/pew.wav", Group::Sfx, 1),
+        ("startup", "audio/startup.wav", Group::Ui, 2),
+        ("win", "audio/win.wav", Group::Sfx, 4),
+        ("ufo", "audio/ufo.wav", Group::Sfx, 2)
+    ];
+    // a sound that won't load is left out, the game plays on without it
+    for (name, path, group, priority) in sounds {
+        if let Err(e) = audio.add(name, path, group, priority) {
+            eprintln!("Sound {} Not Loaded: {}", name, e);
+        }
+    }
+    for (group, volume) in config.volume.clone().unwrap_or_default() {
+        audio.set_volume(group, volume);
+    }
+
+    // load level files, the game generates its own levels without them
+    let levels = Level::load_dir(LEVEL_DIR)?;
+
+    // playfield size comes from the config file or the terminal
+    let mut field = config.playfield();
+    render::set_color(render::color_from_env(config.color.unwrap_or(true)));
+
     // init terminal
     let mut stdout = io::stdout();
     terminal::enable_raw_mode()?;
@@ -32,37 +318,57 @@ async fn main() -> Result<(), Box<dyn Error>> {
 
     // Render loop in a seperate thread
     let (tx, rx) = mpsc::channel::<Frame>();
+    // rendered frames come back through the pool to be drawn on again
+    let (frames, recycle_tx) = FramePool::new();
     let handle = thread::spawn(move || {
-        let mut last_frame = frame::new_frame();
+        let mut last_frame = frame::new_frame(field);
         let mut stdout = io::stdout();
-        render(&mut stdout, &last_frame, &last_frame, true);
-        loop {
-            let curr_frame = match rx.recv() {
-                Ok(x) => x,
-                Err(_) => break,
-            };
-            render(&mut stdout, &last_frame, &curr_frame, false);
-            last_frame = curr_frame.to_vec();
+        // forces a full redraw whenever the terminal changes size
+        let mut last_size = None;
+        while let Ok(curr_frame) = rx.recv() {
+            let size = terminal::size().ok();
+            let curr_field = curr_frame.field();
+            let fits = size.is_none_or(|(cols, rows)| cols as usize >= curr_field.cols && rows as usize >= curr_field.rows);
+            if !fits {
+                if size != last_size {
+                    render_too_small(&mut stdout, curr_field);
+                    last_size = size;
+                }
+                let _ = recycle_tx.send(curr_frame);
+                continue;
+            }
+            render(&mut stdout, &last_frame, &curr_frame, size != last_size);
+            last_size = size;
+            let _ = recycle_tx.send(mem::replace(&mut last_frame, curr_frame));
         }
     });
 
     audio.play("startup");
+    // highlighted on the leaderboard and used to jump to the player's rank
+    let mut my_name = play_args.name.clone();
 
     'mainloop: loop {
-        let mut instant = Instant::now();
+        if system.menu != Menu::Game {
+            // the game track pauses in the menus and picks up again in the next game
+            audio.play_music(Some(Track::Menu));
+        }
         if system.menu == Menu::Main {
             let text = 
             " Welcome To Space Invaders\n
-       1: Play Game\n
-       2: Leaderboard\n
-       3: How To Play\n
-       4: Exit";
-            let main_menu = NewMenu::new(text.to_string(), 6, 5);
+      1: Play Game\n
+      2: Leaderboard\n
+      3: How To Play\n
+      4: Exit";
             'mainmenu: loop {
-                let mut curr_frame = frame::new_frame();
+                let mut curr_frame = frames.next(field);
                 while event::poll(Duration::default())? {
-                    if let Event::Key(key_event) = event::read()? {
-                        match key_event.code {
+                    match event::read()? {
+                        // new games take the new terminal size unless the config pins it
+                        Event::Resize(_, _) if !config.fixed_playfield() => {
+                            field = config.playfield();
+                            curr_frame.reset(field);
+                        }
+                        Event::Key(key_event) => match key_event.code {
                             KeyCode::Char('1') => {
                                 system.menu = Menu::Game;
                                 render(&mut stdout, &curr_frame, &curr_frame, true);
@@ -81,54 +387,63 @@ async fn main() -> Result<(), Box<dyn Error>> {
                             KeyCode::Char('4') | KeyCode::Esc => {
                                 break 'mainloop;
                             }
+                            KeyCode::Char('m') => audio.toggle_mute(),
                             _ => {}
                         }
+                        _ => {}
                     }
                 }
-                main_menu.draw(&mut curr_frame);
+                NewMenu::centered(text.to_string(), field).highlight(0).draw(&mut curr_frame);
+                draw_audio_status(audio.as_ref(), &mut curr_frame);
                 let _ = tx.send(curr_frame);
+                audio.update();
                 thread::sleep(Duration::from_millis(1));
             }
         }
         if system.menu == Menu::Game {
-            let mut player = Player::new();
-            let mut invaders = Invaders::new(1);
-            let mut valid_name = true;
-            'game: loop {
-                // frame init
-                let mut curr_frame = frame::new_frame();
-                let delta = instant.elapsed();
-                instant = Instant::now();
-                if !valid_name {
-                    system.menu = Menu::Main;
-                    render(&mut stdout, &curr_frame, &curr_frame, true);
-                    break 'game;
-                }
-                if player.name.is_empty() {
-                    valid_name = player.get_name();
-                    render(&mut stdout, &curr_frame, &curr_frame, true);
+            let seed = play_args.seed.unwrap_or_else(Rng::seed_from_time);
+            let mut game = Game::with_levels(seed, levels.clone(), field);
+            game.set_speed_curve(config.speed_curve.unwrap_or_default());
+            if let Some(level) = play_args.level {
+                game.start_at(level);
+            }
+            let valid_name = match &play_args.name {
+                Some(name) => {
+                    game.player.name = name.clone();
+                    true
                 }
-                let score_str = format!("Level: {}, Score: {}", player.level, player.score);
-                let score_display = NewMenu::new(score_str, 8, 0);
+                None => game.player.get_name()
+            };
+            render(&mut stdout, &frame::new_frame(field), &frame::new_frame(field), true);
+            if !valid_name {
+                system.menu = Menu::Main;
+                continue 'mainloop;
+            }
+            my_name = Some(game.player.name.clone());
+            audio.play_music(Some(Track::Game));
+            let mut timestep = FixedTimestep::new();
+            let mut limiter = FrameLimiter::new(config.fps.unwrap_or(DEFAULT_FPS));
+            // input waits here for the next tick
+            let mut commands = Vec::new();
+            'game: loop {
                 // Input
                 while event::poll(Duration::default())? {
                     if let Event::Key(key_event) = event::read()? {
                         match key_event.code {
                             KeyCode::Left | KeyCode::Char('a')=> {
-                                player.move_left();
+                                commands.push(Command::MoveLeft);
                             }
                             KeyCode::Right | KeyCode::Char('d') => {
-                                player.move_right();
+                                commands.push(Command::MoveRight);
                             }
                             KeyCode::Char(' ') => {
-                                if player.shoot() {
-                                    audio.play("pew");
-                                }
+                                commands.push(Command::Shoot);
                             }
+                            KeyCode::Char('m') => audio.toggle_mute(),
                             KeyCode::Esc | KeyCode::Char('q') => {
-                                audio.stop();
+                                audio.stop(Group::Sfx);
                                 audio.play("lose");
-                                render(&mut stdout, &curr_frame, &curr_frame, true);
+                                render(&mut stdout, &frame::new_frame(field), &frame::new_frame(field), true);
                                 system.menu = Menu::Main;
                                 break 'game;
                             }
@@ -136,115 +451,283 @@ async fn main() -> Result<(), Box<dyn Error>> {
                         }
                     }
                 }
-                
-                // render + draw
-                invaders.draw(&mut curr_frame);
-                player.draw(&mut curr_frame);
-                score_display.draw(&mut curr_frame);
-                player.update(delta);
-                if invaders.update(delta) {
-                    audio.play("move");
-                }
-                if player.detect_hits(&mut invaders) {
-                    audio.play("explosion");
+
+                // update in fixed ticks, paused while the terminal is too small to show the playfield
+                let ticks = if field.fits_terminal() {
+                    timestep.advance()
+                } else {
+                    timestep.reset();
+                    commands.clear();
+                    0
+                };
+                let mut events = Vec::new();
+                for _ in 0..ticks {
+                    events.extend(game.update(&commands, TICK));
+                    commands.clear();
                 }
-                
-                // win lose conditions
-                if invaders.all_dead() {
-                    // audio.play("win");
-                    invaders = Invaders::new(player.level + 1);
-                    player.clear_shots();
-                    player.level += 1;
+                for event in events {
+                    match event {
+                        GameEvent::Shot => audio.play("pew"),
+                        GameEvent::InvadersMoved => audio.play("move"),
+                        GameEvent::Hit | GameEvent::PlayerHit => audio.play("explosion"),
+                        GameEvent::UfoSpawned => audio.play("ufo"),
+                        GameEvent::UfoHit(_) => audio.play("explosion"),
+                        GameEvent::Bomb => {}
+                        GameEvent::LevelCleared(_) => {}
+                        GameEvent::GameOver => {
+                            audio.stop(Group::Sfx);
+                            audio.play("lose");
+                        }
+                    }
                 }
-                if invaders.reached_bottom() {
-                    audio.stop();
-                    audio.play("lose");
-                    render(&mut stdout, &frame::new_frame(), &frame::new_frame(), true);
+                if game.over {
+                    render(&mut stdout, &frame::new_frame(field), &frame::new_frame(field), true);
                     system.menu = Menu::Main;
                     break 'game;
-                }       
-                let _ = tx.send(curr_frame);
+                }
+
+                audio.set_tempo(game.invaders.beat());
+
+                // render + draw
+                if limiter.ready() {
+                    let mut curr_frame = frames.next(field);
+                    game.draw(&mut curr_frame);
+                    draw_audio_status(audio.as_ref(), &mut curr_frame);
+                    let _ = tx.send(curr_frame);
+                }
+                audio.update();
                 thread::sleep(Duration::from_millis(1));
             }
-            let text = match client.update_scores(SystemPlayer {name: player.name.clone(), score: player.score, is: "score".to_string()}).await {
-                Ok(str) => str,
-                Err(e) => e.to_string()
+            let replay = Replay::from_game(&game);
+            let replay_text = match replay.save(REPLAY_DIR) {
+                Ok(path) => format!("Replay Saved: {}", path.file_name().map(|name| name.to_string_lossy()).unwrap_or_default()),
+                Err(e) => format!("Replay Not Saved: {}", e)
+            };
+            let game_over_text = format!("Game Over\n\nLevel Reached: {}\n\nFinal Score: {}\n\n{}", game.player.level, game.player.score, replay_text);
+            audio.play_music(Some(Track::Menu));
+            let game_over_display = NewMenu::centered_at(game_over_text, 3, field).highlight(0);
+            let mut curr_frame = frames.next(field);
+            game_over_display.draw(&mut curr_frame);
+            NewMenu::centered_at("Submitting Score...".to_string(), 11, field).draw(&mut curr_frame);
+            let _ = tx.send(curr_frame);
+
+            // every game goes on the local leaderboard, scores the server missed wait for the next sync
+            // the proof lets the server play the game back before it takes the score
+            let score = LocalScore::new(game.player.name.clone(), game.player.score, game.player.level)
+                .with_proof(ScoreProof::from_replay(&replay));
+            let mut new_high_score = false;
+            let (text, queue) = if cli.offline {
+                ("Offline, Score Saved Locally".to_string(), false)
+            } else {
+                let refused = sync_scores(&client, &mut system.scores).await.unwrap_or(0);
+                let (text, queue) = match client.update_scores(score.player()).await {
+                    Ok(response) => {
+                        new_high_score = response.new_high_score;
+                        (response.to_string(), false)
+                    }
+                    Err(e) if e.should_queue() => (format!("{}\nScore Saved, Will Submit Later", e), true),
+                    Err(e) => (format!("Score Not Submitted:\n{}", e), false)
+                };
+                match refused {
+                    0 => (text, queue),
+                    n => (format!("{}\n{}", text, refused_text(n)), queue)
+                }
             };
-            let beat_score_display = NewMenu::new(text, 5, 10);
+            let text = match system.scores.record(score, queue) {
+                Ok(()) => text,
+                Err(e) => format!("{}\nScore Not Saved: {}", text, e)
+            };
+            let beat_score_display = NewMenu::centered_at(text, 11, field);
+            let beat_score_display = if new_high_score { beat_score_display.highlight(0) } else { beat_score_display };
 
             'endgame: loop {
-                let mut curr_frame = frame::new_frame();
+                let mut curr_frame = frames.next(field);
                 while event::poll(Duration::default())? {
                     if let Event::Key(key_event) = event::read()? {
-                        match key_event.code {
-                            KeyCode::Esc => {
-                                render(&mut stdout, &frame::new_frame(), &frame::new_frame(), true);
-                                system.menu = Menu::Main;
-                                break 'endgame;
-                            },
-                            _ => {}
+                        if key_event.code == KeyCode::Esc {
+                            render(&mut stdout, &frame::new_frame(field), &frame::new_frame(field), true);
+                            system.menu = Menu::Main;
+                            break 'endgame;
                         }
                     }
                 }
+                game_over_display.draw(&mut curr_frame);
                 beat_score_display.draw(&mut curr_frame);
                 let _ = tx.send(curr_frame);
+                audio.update();
                 thread::sleep(Duration::from_millis(1));
             }
         }
         if system.menu == Menu::Leaderboard {
-            let text = match client.get_scores().await {
-                Ok(scores) => {
-                    let mut tmp = String::from("Space Invaders Leaderboars\n\n");
-                    for (i, p) in scores.iter().enumerate() {
-                        tmp.push_str(&(" ".to_string() + &(i + 1).to_string().as_str() + ": " + &p.name + " With a Score of " + &p.score.to_string() + "\n\n"))
-                    }
-                    tmp
-                },
-                Err(e) => format!("{}", e)
-            };
-            let leaderboard_menu = NewMenu::new(text, 5, 2);
+            // the title, filter, status and column header sit above the scores, the key help below
+            let limit = field.rows.saturating_sub(8).max(1);
+            let mut period = Period::All;
+            let mut offset: usize = 0;
+            let mut around: Option<String> = None;
+            let mut page = ScorePage::default();
+            let mut message: Option<String> = None;
+            // the local leaderboard stands in for the rest of the visit once the server can't be reached, with why
+            let mut local = if cli.offline { Some("Offline".to_string()) } else { None };
+            let mut loading: Option<PageTask> = None;
+            // the first request syncs queued scores before asking for the page, `finish_sync` once it's done or abandoned
+            let progress = Arc::new(SyncProgress::default());
+            let mut sync = Some((system.scores.pending.clone(), progress.clone()));
+            let mut synced = false;
+            let mut wanted = true;
+            let started = Instant::now();
 
             'leaderboard: loop {
-                let mut curr_frame = frame::new_frame();
+                let mut curr_frame = frames.next(field);
                 while event::poll(Duration::default())? {
                     if let Event::Key(key_event) = event::read()? {
-                        match key_event.code {
+                        let last = page.total.saturating_sub(limit);
+                        let next = match key_event.code {
                             KeyCode::Esc => {
+                                if let Some(task) = loading.take() {
+                                    task.abort();
+                                    // the task is only stopped once awaited, after that nothing more is sent
+                                    let _ = task.await;
+                                }
+                                if !synced {
+                                    let _ = finish_sync(&mut system.scores, &progress);
+                                }
                                 system.menu = Menu::Main;
                                 render(&mut stdout, &curr_frame, &curr_frame, true);
                                 break 'leaderboard;
                             }
-                            _ => {}
+                            KeyCode::Up => Some(offset.saturating_sub(1)),
+                            KeyCode::Down => Some((offset + 1).min(last)),
+                            KeyCode::PageUp => Some(offset.saturating_sub(limit)),
+                            KeyCode::PageDown => Some((offset + limit).min(last)),
+                            KeyCode::Home => Some(0),
+                            KeyCode::End => Some(last),
+                            KeyCode::Right | KeyCode::Char('f') | KeyCode::Char('F') => {
+                                period = period.next();
+                                Some(0)
+                            }
+                            KeyCode::Left => {
+                                period = period.next().next();
+                                Some(0)
+                            }
+                            KeyCode::Char('j') | KeyCode::Char('J') => {
+                                match &my_name {
+                                    Some(name) => around = Some(name.clone()),
+                                    None => message = Some("Play A Game To Find Your Rank".to_string())
+                                }
+                                Some(offset)
+                            }
+                            KeyCode::Char('m') => {
+                                audio.toggle_mute();
+                                None
+                            }
+                            _ => None
+                        };
+                        if let Some(next) = next {
+                            wanted |= next != offset || period != page.period || around.is_some();
+                            offset = next;
+                        }
+                    }
+                }
+
+                if loading.as_ref().is_some_and(|task| task.is_finished()) {
+                    let result = match loading.take().unwrap().await {
+                        Ok(result) => result,
+                        Err(e) => Err(LeaderboardError::Server(e.to_string()))
+                    };
+                    let refused = if synced { 0 } else { finish_sync(&mut system.scores, &progress).unwrap_or(0) };
+                    synced = true;
+                    match result {
+                        Ok(next) => {
+                            offset = next.offset;
+                            page = next;
+                            message = None;
+                            around = None;
+                        }
+                        // the player just isn't on this board, the page stays as it was
+                        Err(LeaderboardError::Server(e)) if around.is_some() => {
+                            message = Some(e);
+                            around = None;
+                        }
+                        Err(e) => {
+                            local = Some(e.to_string());
+                            wanted = true;
+                        }
+                    }
+                    if refused > 0 {
+                        message = Some(refused_text(refused));
+                    }
+                }
+                // keys pressed while a page loads are caught up with once it arrives
+                if wanted && loading.is_none() {
+                    wanted = false;
+                    if local.is_some() {
+                        match system.scores.page(period, offset, limit, around.as_deref(), scores::now()) {
+                            Ok(next) => {
+                                offset = next.offset;
+                                page = next;
+                                message = None;
+                            }
+                            Err(e) => message = Some(e)
                         }
+                        around = None;
+                    } else {
+                        loading = Some(load_page(&client, sync.take(), period, offset, limit, around.clone()));
                     }
                 }
-                leaderboard_menu.draw(&mut curr_frame);
+
+                let title = match &local {
+                    Some(reason) => format!("Local Leaderboard ({})", reason),
+                    None => "Space Invaders Leaderboard".to_string()
+                };
+                NewMenu::centered_at(title, 1, field).highlight(0).draw(&mut curr_frame);
+                NewMenu::centered_at(format!("< {} >", period.name()), 2, field).draw(&mut curr_frame);
+                let status = if loading.is_some() {
+                    format!("Loading {}", spinner(started))
+                } else if let Some(message) = &message {
+                    message.clone()
+                } else if page.total == 0 {
+                    "No Scores Yet".to_string()
+                } else {
+                    format!("{}-{} of {}", page.offset + 1, page.offset + page.scores.len(), page.total)
+                };
+                NewMenu::centered_at(status, 3, field).color(Color::DarkGrey).draw(&mut curr_frame);
+                let header = leaderboard_line(None);
+                let x = field.cols.saturating_sub(header.chars().count()) / 2;
+                NewMenu::new(header, x, 5).color(Color::DarkGrey).draw(&mut curr_frame);
+                for (i, score) in page.scores.iter().enumerate() {
+                    let line = NewMenu::new(leaderboard_line(Some(score)), x, 6 + i);
+                    let line = if my_name.as_deref() == Some(score.name.as_str()) { line.highlight(0) } else { line };
+                    line.draw(&mut curr_frame);
+                }
+                let help = "Up/Down: Scroll  PgUp/PgDn: Page  F: Filter  J: My Rank  Esc: Back";
+                NewMenu::centered_at(help.to_string(), field.rows.saturating_sub(1), field).color(Color::DarkGrey).draw(&mut curr_frame);
+                draw_audio_status(audio.as_ref(), &mut curr_frame);
                 let _ = tx.send(curr_frame);
+                audio.update();
                 thread::sleep(Duration::from_millis(1));
             }
         }
         if system.menu == Menu::Help {
             let text = 
             "How To Play\n
- Movement: WASD/Arrow Keys\n
- Shoot: Spacebar".to_string();
-            let help_menu = NewMenu::new(text, 12, 2);
+Movement: WASD/Arrow Keys\n
+Shoot: Spacebar\n
+Mute: M".to_string();
+            let help_menu = NewMenu::centered(text, field).highlight(0);
             'help: loop {
-                let mut curr_frame = frame::new_frame();
+                let mut curr_frame = frames.next(field);
                 while event::poll(Duration::default())? {
                     if let Event::Key(key_event) = event::read()? {
-                        match key_event.code {
-                            KeyCode::Esc => {
-                                system.menu = Menu::Main;
-                                render(&mut stdout, &curr_frame, &curr_frame, true);
-                                break 'help;
-                            }
-                            _ => {}
+                        if key_event.code == KeyCode::Esc {
+                            system.menu = Menu::Main;
+                            render(&mut stdout, &curr_frame, &curr_frame, true);
+                            break 'help;
                         }
                     }
                 }
                 help_menu.draw(&mut curr_frame);
                 let _ = tx.send(curr_frame);
+                audio.update();
                 thread::sleep(Duration::from_millis(1));
             }
         }
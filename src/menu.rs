@@ -1,4 +1,6 @@
-use crate::{ frame::{Frame, Drawable}};
+use crossterm::style::{Color, Attribute};
+
+use crate::{ frame::{Frame, Drawable, Cell}, Playfield};
 
 #[derive(PartialEq)]
 pub enum Menu {
@@ -11,43 +13,65 @@ pub enum Menu {
 pub struct NewMenu {
     text: String,
     x: usize,
-    y: usize
+    y: usize,
+    color: Color,
+    highlight: Option<usize>
 }
 
 impl NewMenu {
     pub fn new(text: String, x: usize, y: usize) -> Self {
-        Self { text, x, y }
+        Self { text, x, y, color: Color::White, highlight: None }
+    }
+    pub fn color(mut self, color: Color) -> Self {
+        self.color = color;
+        self
+    }
+    /**
+     * desc: draws the `line`th line of the text highlighted, e.g. a title
+     */
+    pub fn highlight(mut self, line: usize) -> Self {
+        self.highlight = Some(line);
+        self
+    }
+    /**
+     * desc: menu centered in the playfield
+     */
+    pub fn centered(text: String, field: Playfield) -> Self {
+        let width = text.lines().map(|line| line.chars().count()).max().unwrap_or(0);
+        let height = text.split('\n').count();
+        Self::new(text, field.cols.saturating_sub(width) / 2, field.rows.saturating_sub(height) / 2)
+    }
+    /**
+     * desc: menu centered horizontally at row `y`
+     */
+    pub fn centered_at(text: String, y: usize, field: Playfield) -> Self {
+        let width = text.lines().map(|line| line.chars().count()).max().unwrap_or(0);
+        Self::new(text, field.cols.saturating_sub(width) / 2, y)
     }
 }
 
 impl Drawable for NewMenu {
     fn draw(&self, frame: &mut Frame) {
-        let mut new_line = false;
-        let mut y_opp = self.y;
-        let mut x_opp = self.x;
+        let mut x = self.x;
+        let mut y = self.y;
+        let mut line = 0;
         for c in self.text.chars() {
-            let y = if new_line {
-                y_opp += 1;
-                y_opp
-            }
-            else {
-                y_opp
-            };
-            let x = if new_line {
-                x_opp = self.x;
-                x_opp
-            }
-            else {
-                x_opp += 1;
-                x_opp - 1
-            };
             if c == '\n' {
-                new_line = true;
+                x = self.x;
+                y += 1;
+                line += 1;
+                continue;
             }
-            else {
-                new_line = false;
-                frame[x][y] = c.to_string();
+            // text running past the frame is clipped
+            if x < frame.cols() && y < frame.rows() {
+                let cell = Cell::new(c);
+                frame[x][y] = if self.highlight == Some(line) {
+                    cell.fg(Color::Yellow).attribute(Attribute::Bold)
+                } else {
+                    cell.fg(self.color)
+                };
             }
+            x += 1;
         }
     }
 }
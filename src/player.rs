@@ -1,21 +1,26 @@
 use std::{time::Duration, io::{self, Write}};
 
+use rusty_time::timer::Timer;
+use crossterm::style::{Color, Attribute};
 use crossterm::{QueueableCommand, cursor::MoveTo, event::{self, Event, KeyCode}};
 
-use crate::{NUM_COLS, NUM_ROWS, frame::Drawable, shot::{Shot}, SHOT_COUNT, invaders::Invaders};
+use crate::{Playfield, frame::{Drawable, Cell}, shot::{Shot, ShotDirection}, SHOT_COUNT, invaders::Invaders, bunkers::Bunkers, ufo::Ufo, rng::Rng, PLAYER_LIVES, RESPAWN_MILLIS};
 
 pub struct Player {
     x: usize,
     y: usize,
+    field: Playfield,
     shots: Vec<Shot>,
     pub name: String,
     pub score: u32,
-    pub level: u128
+    pub level: u128,
+    pub lives: u32,
+    invulnerable: Option<Timer>
 }
 
 impl Player {
-    pub fn new() -> Self {
-        Self { x: NUM_COLS / 2, y: NUM_ROWS - 1, shots: Vec::new(), name: String::new(), score: 0, level: 1 }
+    pub fn new(field: Playfield) -> Self {
+        Self { x: field.cols / 2, y: field.rows - 1, field, shots: Vec::new(), name: String::new(), score: 0, level: 1, lives: PLAYER_LIVES, invulnerable: None }
     }
     pub fn move_left(&mut self) {
         if self.x > 0 {
@@ -23,13 +28,13 @@ impl Player {
         }
     }
     pub fn move_right(&mut self) {
-        if self.x < NUM_COLS - 1 {
+        if self.x < self.field.cols - 1 {
             self.x += 1;
         }
     }
     pub fn shoot(&mut self) -> bool {
         if self.shots.len() < SHOT_COUNT {
-            self.shots.push(Shot::new(self.x, self.y - 1));
+            self.shots.push(Shot::new(self.x, self.y - 1, ShotDirection::Up, self.field));
             return true;
         }
         false 
@@ -39,17 +44,72 @@ impl Player {
             shot.update(delta);
         }
         self.shots.retain(|shot| !shot.dead());
+        if let Some(timer) = self.invulnerable.as_mut() {
+            timer.update(delta);
+            if timer.ready {
+                self.invulnerable = None;
+            }
+        }
+    }
+    pub fn detect_hits(&mut self, invaders: &mut Invaders, bunkers: &mut Bunkers) -> bool {
+        for shot in self.shots.iter_mut() {
+            if shot.exploding || bunkers.absorb(shot) {
+                continue;
+            }
+            if let Some(points) = invaders.hit_at(shot.x, shot.y) {
+                shot.explode();
+                self.score += points;
+                return true;
+            }
+        }
+        false
     }
-    pub fn detect_hits(&mut self, invaders: &mut Invaders) -> bool {
+    /**
+     * desc: checks the shots against the ufo on the top row, returns the bonus scored
+     */
+    pub fn detect_ufo_hit(&mut self, ufo: &mut Ufo, rng: &mut Rng) -> Option<u32> {
         for shot in self.shots.iter_mut() {
-            if invaders.kill_at(shot.x, shot.y) && !shot.exploding {
+            if shot.exploding {
+                continue;
+            }
+            if let Some(bonus) = ufo.hit_at(shot.x, shot.y, rng) {
+                shot.explode();
+                self.score += bonus;
+                return Some(bonus);
+            }
+        }
+        None
+    }
+    /**
+     * desc: checks the invaders' bombs against the player's cell
+     */
+    pub fn detect_hit(&self, invaders: &mut Invaders) -> bool {
+        if self.is_invulnerable() {
+            return false;
+        }
+        for shot in invaders.shots.iter_mut() {
+            if shot.x == self.x && shot.y == self.y && !shot.exploding {
                 shot.explode();
-                self.score += 1;
                 return true;
             }
         }
         false
     }
+    /**
+     * desc: takes a life and respawns the player, returns false when there are no lives left
+     */
+    pub fn lose_life(&mut self) -> bool {
+        self.lives = self.lives.saturating_sub(1);
+        if self.lives == 0 {
+            return false;
+        }
+        self.x = self.field.cols / 2;
+        self.invulnerable = Some(Timer::from_millis(RESPAWN_MILLIS));
+        true
+    }
+    pub fn is_invulnerable(&self) -> bool {
+        self.invulnerable.is_some()
+    }
     pub fn get_name(&mut self) -> bool {
         let mut stdout = io::stdout();
         stdout.queue(MoveTo(5, 3)).unwrap();
@@ -77,7 +137,7 @@ impl Player {
                             break true;
                         }
                         _ => {
-                            for i in 33..176 as u8 {
+                            for i in 33..176_u8 {
                                 if key.code == KeyCode::Char(i as char) {
                                     input.push(i as char);
                                     stdout.queue(MoveTo(27 + characters, 3)).unwrap();
@@ -101,10 +161,22 @@ impl Player {
     }
 }
 
+impl Default for Player {
+    fn default() -> Self {
+        Self::new(Playfield::default())
+    }
+}
+
 impl Drawable for Player {
     fn draw(&self, frame: &mut crate::frame::Frame) {
-        // draw player
-        frame[self.x][self.y] = "A".to_string();
+        // draw player, blinking while invulnerable
+        let visible = match &self.invulnerable {
+            Some(timer) => timer.time_left.as_millis() / 150 % 2 == 0,
+            None => true
+        };
+        if visible {
+            frame[self.x][self.y] = Cell::new('A').fg(Color::Yellow).attribute(Attribute::Bold);
+        }
         // draw shots
         for shot in self.shots.iter() {
             shot.draw(frame);
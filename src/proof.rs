@@ -0,0 +1,225 @@
+use std::time::Duration;
+use serde::{Serialize, Deserialize};
+use sha2::{Digest, Sha256};
+
+use crate::{Playfield, MAX_COLS, MAX_ROWS, game::{Command, Input}, invaders::SpeedCurve, level::Level, replay::Replay, system::SystemPlayer, timestep::{TICK, TICK_RATE}};
+
+/// longest game a proof can cover, two hours, so checking one stays quick
+pub const MAX_TICKS: u64 = TICK_RATE as u64 * 60 * 60 * 2;
+/// most inputs a log can hold, a few commands every tick of the longest game
+const MAX_INPUTS: u64 = MAX_TICKS * 4;
+
+/// Proof sent with a score that it came from a game that was really played: everything needed
+/// to play the game back but the levels and speed curve, which the checker brings itself, and its
+/// input log compressed by `compress_inputs`.
+/// It isn't signed, the API key is shared with every player so a signature keyed with it proves nothing,
+/// the score stands or falls on the server playing the game back.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ScoreProof {
+    pub seed: u64,
+    /// build of the game, a game only plays back the same on the build it was played on
+    pub version: String,
+    /// ticks the game ran for, see `duration`
+    pub ticks: u64,
+    /// level reached
+    pub level: u128,
+    pub start_level: u128,
+    pub field: Playfield,
+    /// `rules_hash` of the levels and speed curve the game was played with
+    pub rules: String,
+    /// `compress_inputs` of every input, in hex
+    pub inputs: String
+}
+
+impl ScoreProof {
+    /**
+     * desc: the proof for the recorded game
+     */
+    pub fn from_replay(replay: &Replay) -> Self {
+        Self {
+            seed: replay.seed,
+            version: replay.version.clone(),
+            ticks: replay.ticks,
+            level: replay.level,
+            start_level: replay.start_level,
+            field: replay.field,
+            rules: rules_hash(&replay.levels, replay.speed_curve),
+            inputs: to_hex(&compress_inputs(&replay.inputs))
+        }
+    }
+    /**
+     * desc: how long the game ran
+     */
+    pub fn duration(&self) -> Duration {
+        TICK.mul_f64(self.ticks as f64)
+    }
+    /**
+     * desc: the recorded game the proof stands for played with `levels` and `speed_curve`,
+     * errors when the input log is broken
+     */
+    pub fn replay(&self, name: &str, score: u32, levels: &[Level], speed_curve: SpeedCurve) -> Result<Replay, String> {
+        let inputs = match from_hex(&self.inputs) {
+            Some(bytes) => decompress_inputs(&bytes)?,
+            None => return Err("Input Log Isn't Hex".to_string())
+        };
+        Ok(Replay {
+            version: self.version.clone(),
+            name: name.to_string(),
+            seed: self.seed,
+            field: self.field,
+            levels: levels.to_vec(),
+            start_level: self.start_level,
+            speed_curve,
+            ticks: self.ticks,
+            inputs,
+            score,
+            level: self.level
+        })
+    }
+}
+
+/**
+ * desc: SHA-256 in hex of the levels and speed curve a game is played with, a proof carries it
+ * instead of the definitions so they can't be swapped for easier ones
+ */
+pub fn rules_hash(levels: &[Level], speed_curve: SpeedCurve) -> String {
+    to_hex(&Sha256::digest(serde_json::to_vec(&(levels, speed_curve)).unwrap_or_default()))
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    if hex.len() % 2 != 0 || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    (0..hex.len()).step_by(2).map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok()).collect()
+}
+
+fn command_byte(command: Command) -> u8 {
+    match command {
+        Command::MoveLeft => 0,
+        Command::MoveRight => 1,
+        Command::Shoot => 2
+    }
+}
+
+fn byte_command(byte: u8) -> Option<Command> {
+    match byte {
+        0 => Some(Command::MoveLeft),
+        1 => Some(Command::MoveRight),
+        2 => Some(Command::Shoot),
+        _ => None
+    }
+}
+
+/**
+ * desc: appends `value` 7 bits at a time, lowest first, the top bit set on every byte but the last
+ */
+fn push_varint(bytes: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        bytes.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    bytes.push(value as u8);
+}
+
+fn read_varint(bytes: &[u8], at: &mut usize) -> Option<u64> {
+    let mut value = 0;
+    for shift in (0..64).step_by(7) {
+        let byte = *bytes.get(*at)?;
+        *at += 1;
+        value |= ((byte & 0x7f) as u64) << shift;
+        if byte & 0x80 == 0 {
+            return Some(value);
+        }
+    }
+    None
+}
+
+/**
+ * desc: run length encodes an input log, each input is the ticks since the one before it and its command,
+ * and a run of identical ones, like a held key, is stored once as `count, ticks, command`
+ */
+pub fn compress_inputs(inputs: &[Input]) -> Vec<u8> {
+    let mut runs: Vec<(u64, u64, u8)> = Vec::new();
+    let mut last = 0;
+    for input in inputs {
+        let gap = input.tick.saturating_sub(last);
+        last = input.tick;
+        let command = command_byte(input.command);
+        match runs.last_mut() {
+            Some((count, run_gap, run_command)) if *run_gap == gap && *run_command == command => *count += 1,
+            _ => runs.push((1, gap, command))
+        }
+    }
+    let mut bytes = Vec::new();
+    for (count, gap, command) in runs {
+        push_varint(&mut bytes, count);
+        push_varint(&mut bytes, gap);
+        bytes.push(command);
+    }
+    bytes
+}
+
+/**
+ * desc: the input log `compress_inputs` made, errors on anything it couldn't have made
+ */
+pub fn decompress_inputs(bytes: &[u8]) -> Result<Vec<Input>, String> {
+    let broken = || "Input Log Is Broken".to_string();
+    let mut inputs = Vec::new();
+    let mut tick: u64 = 0;
+    let mut at = 0;
+    while at < bytes.len() {
+        let count = read_varint(bytes, &mut at).ok_or_else(broken)?;
+        let gap = read_varint(bytes, &mut at).ok_or_else(broken)?;
+        let command = bytes.get(at).and_then(|byte| byte_command(*byte)).ok_or_else(broken)?;
+        at += 1;
+        if count == 0 || inputs.len() as u64 + count > MAX_INPUTS {
+            return Err(broken());
+        }
+        for _ in 0..count {
+            tick = tick.checked_add(gap).ok_or_else(broken)?;
+            inputs.push(Input { tick, command });
+        }
+    }
+    Ok(inputs)
+}
+
+/**
+ * desc: checks the proof sent with a score and plays the game back with the checker's own `levels`
+ * and `speed_curve`, errors with why the score was rejected
+ */
+pub fn verify(player: &SystemPlayer, levels: &[Level], speed_curve: SpeedCurve) -> Result<(), String> {
+    let proof = match &player.proof {
+        Some(proof) => proof,
+        None => return Err("Score Has No Proof".to_string())
+    };
+    if proof.version != env!("CARGO_PKG_VERSION") {
+        return Err(format!("Score From Another Version Of The Game ({})", proof.version));
+    }
+    if proof.rules != rules_hash(levels, speed_curve) {
+        return Err("Score Played With Different Levels Or Speed Curve".to_string());
+    }
+    if proof.ticks > MAX_TICKS {
+        return Err("Game Too Long To Check".to_string());
+    }
+    // anything the game itself could never have been set up with
+    let field = proof.field;
+    if field != Playfield::new(field.cols.min(MAX_COLS), field.rows.min(MAX_ROWS)) {
+        return Err("Invalid Playfield".to_string());
+    }
+    if proof.start_level == 0 || proof.start_level > proof.level {
+        return Err("Invalid Level".to_string());
+    }
+    let replay = proof.replay(&player.name, player.score, levels, speed_curve)?;
+    if replay.inputs.last().is_some_and(|input| input.tick > proof.ticks) {
+        return Err("Input Log Runs Past The End Of The Game".to_string());
+    }
+    let game = replay.simulate();
+    if game.player.score != player.score || game.player.level != proof.level {
+        return Err(format!("Impossible Score, The Game Plays Back To {} On Level {}", game.player.score, game.player.level));
+    }
+    Ok(())
+}
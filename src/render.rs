@@ -1,22 +1,85 @@
-use std::io::{Stdout, Write};
+use std::{env, io::{Stdout, Write}, sync::atomic::{AtomicBool, Ordering}};
 
-use crossterm::{QueueableCommand, style::{SetBackgroundColor, Color}, terminal::{Clear, ClearType}, cursor::MoveTo};
+use crossterm::{QueueableCommand, style::{SetBackgroundColor, SetForegroundColor, SetAttribute, SetAttributes, Color, Attribute, Attributes}, terminal::{Clear, ClearType}, cursor::MoveTo};
 
-use crate::frame::Frame;
+use crate::{frame::{Frame, Cell}, Playfield};
+
+static COLOR: AtomicBool = AtomicBool::new(true);
+
+/**
+ * desc: switches between color and monochrome rendering
+ */
+pub fn set_color(enabled: bool) {
+    COLOR.store(enabled, Ordering::Relaxed);
+}
+
+pub fn color_enabled() -> bool {
+    COLOR.load(Ordering::Relaxed)
+}
+
+/**
+ * desc: a non empty `NO_COLOR` env var turns colors off whatever the config says
+ */
+pub fn color_from_env(configured: bool) -> bool {
+    configured && env::var_os("NO_COLOR").is_none_or(|value| value.is_empty())
+}
+
+type Style = (Color, Color, Attributes);
+
+fn apply_style(stdout: &mut Stdout, cell: &Cell, current: &mut Option<Style>, color: bool) {
+    let style = if color {
+        (cell.fg, cell.bg, cell.attributes)
+    } else {
+        (Color::Reset, Color::Reset, cell.attributes)
+    };
+    if *current == Some(style) {
+        return;
+    }
+    // resets the colors as well as the attributes
+    stdout.queue(SetAttribute(Attribute::Reset)).unwrap();
+    if !cell.attributes.is_empty() {
+        stdout.queue(SetAttributes(cell.attributes)).unwrap();
+    }
+    if color {
+        stdout.queue(SetForegroundColor(cell.fg)).unwrap();
+        stdout.queue(SetBackgroundColor(cell.bg)).unwrap();
+    }
+    *current = Some(style);
+}
 
 pub fn render(stdout: &mut Stdout, last_frame: &Frame, curr_frame: &Frame, force: bool) {
-    if force == true {
-        stdout.queue(SetBackgroundColor(Color::Blue)).unwrap();
+    let color = color_enabled();
+    // a resized frame can't be diffed against the last one
+    let force = force || last_frame.field() != curr_frame.field();
+    if force {
+        stdout.queue(SetAttribute(Attribute::Reset)).unwrap();
+        if color {
+            stdout.queue(SetBackgroundColor(Color::Blue)).unwrap();
+        }
         stdout.queue(Clear(ClearType::All)).unwrap();
-        stdout.queue(SetBackgroundColor(Color::Black)).unwrap();
     }
-    for (x, col) in curr_frame.iter().enumerate() {
-        for (y, str) in col.iter().enumerate() {
-            if *str != last_frame[x][y] || force {
+    let mut style = None;
+    for x in 0..curr_frame.cols() {
+        for (y, cell) in curr_frame[x].iter().enumerate() {
+            if *cell != last_frame[x][y] || force {
                 stdout.queue(MoveTo(x as u16, y as u16)).unwrap();
-                print!("{}", str);
+                apply_style(stdout, cell, &mut style, color);
+                print!("{}", cell.glyph);
             }
         }
     }
     stdout.flush().unwrap();
-}
\ No newline at end of file
+}
+
+/**
+ * desc: replaces the playfield with a notice until the terminal is big enough again
+ */
+pub fn render_too_small(stdout: &mut Stdout, field: Playfield) {
+    stdout.queue(SetAttribute(Attribute::Reset)).unwrap();
+    stdout.queue(Clear(ClearType::All)).unwrap();
+    stdout.queue(MoveTo(0, 0)).unwrap();
+    print!("Terminal Too Small");
+    stdout.queue(MoveTo(0, 1)).unwrap();
+    print!("Resize To At Least {}x{}", field.cols, field.rows);
+    stdout.flush().unwrap();
+}
@@ -0,0 +1,138 @@
+use std::{fs::{self, File}, error::Error, io::Read, path::{Path, PathBuf}, time::{SystemTime, UNIX_EPOCH}};
+use serde::{Serialize, Deserialize};
+
+use crate::{Playfield, game::{Game, GameEvent, Input}, invaders::SpeedCurve, level::Level, timestep::TICK};
+
+pub const REPLAY_DIR: &str = "data/replays";
+
+/// Everything needed to play a game back exactly: the seed, the levels and playfield it
+/// ran with, and every input with the tick it was applied on.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Replay {
+    pub version: String,
+    pub name: String,
+    pub seed: u64,
+    pub field: Playfield,
+    pub levels: Vec<Level>,
+    #[serde(default = "first_level")]
+    pub start_level: u128,
+    /// replays from before the army sped up kept a constant pace
+    #[serde(default = "SpeedCurve::constant")]
+    pub speed_curve: SpeedCurve,
+    /// ticks the game ran for
+    pub ticks: u64,
+    pub inputs: Vec<Input>,
+    /// results as recorded, for checking against the playback
+    pub score: u32,
+    pub level: u128
+}
+
+fn first_level() -> u128 {
+    1
+}
+
+impl Replay {
+    pub fn from_game(game: &Game) -> Self {
+        Self {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            name: game.player.name.clone(),
+            seed: game.seed,
+            field: game.field,
+            levels: game.levels.clone(),
+            start_level: game.start_level,
+            speed_curve: game.speed_curve,
+            ticks: game.tick,
+            inputs: game.inputs.clone(),
+            score: game.player.score,
+            level: game.player.level
+        }
+    }
+    pub fn load(path: &str) -> Result<Replay, Box<dyn Error>> {
+        let mut file = match File::open(path) {
+            Ok(file) => file,
+            Err(e) => return Err(Box::new(e)),
+        };
+        let mut contents = String::new();
+        match file.read_to_string(&mut contents) {
+            Ok(data) => data,
+            Err(e) => return Err(Box::new(e))
+        };
+        match serde_json::from_str(&contents) {
+            Ok(replay) => Ok(replay),
+            Err(e) => Err(format!("Invalid Replay File {}: {}", path, e).into())
+        }
+    }
+    /**
+     * desc: writes the replay into `dir` as `<name>-<unix time>.json`
+     */
+    pub fn save(&self, dir: &str) -> Result<PathBuf, Box<dyn Error>> {
+        fs::create_dir_all(dir)?;
+        let name: String = self.name.chars().filter(|c| c.is_ascii_alphanumeric()).collect();
+        let time = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+        let path = Path::new(dir).join(format!("{}-{}.json", if name.is_empty() { "player" } else { &name }, time));
+        fs::write(&path, serde_json::to_string(self)?)?;
+        Ok(path)
+    }
+    /**
+     * desc: a fresh game set up the way the recorded one started
+     */
+    pub fn new_game(&self) -> Game {
+        let mut game = Game::with_levels(self.seed, self.levels.clone(), self.field);
+        game.player.name = self.name.clone();
+        game.set_speed_curve(self.speed_curve);
+        if self.start_level > 1 {
+            game.start_at(self.start_level);
+        }
+        game
+    }
+    /**
+     * desc: plays the whole replay back without a terminal
+     */
+    pub fn simulate(&self) -> Game {
+        let mut game = self.new_game();
+        let mut playback = Playback::new(self);
+        while !playback.finished(&game) {
+            playback.step(&mut game);
+        }
+        game
+    }
+    /**
+     * desc: true if playing the inputs back with `levels` and `speed_curve` instead of the ones in the file
+     * reaches the recorded score and level, so a replay edited to easier levels doesn't pass
+     */
+    pub fn verify(&self, levels: &[Level], speed_curve: SpeedCurve) -> bool {
+        let checked = Replay { levels: levels.to_vec(), speed_curve, ..self.clone() };
+        let game = checked.simulate();
+        game.player.score == self.score && game.player.level == self.level
+    }
+}
+
+/// Feeds a replay's inputs back into a game one tick at a time.
+pub struct Playback<'a> {
+    replay: &'a Replay,
+    next: usize
+}
+
+impl<'a> Playback<'a> {
+    pub fn new(replay: &'a Replay) -> Self {
+        Self { replay, next: 0 }
+    }
+    pub fn finished(&self, game: &Game) -> bool {
+        game.over || game.tick >= self.replay.ticks
+    }
+    /**
+     * desc: advances the game one tick with the inputs recorded for it
+     */
+    pub fn step(&mut self, game: &mut Game) -> Vec<GameEvent> {
+        let tick = game.tick + 1;
+        let mut commands = Vec::new();
+        while let Some(input) = self.replay.inputs.get(self.next) {
+            if input.tick > tick {
+                break;
+            }
+            commands.push(input.command);
+            self.next += 1;
+        }
+        game.update(&commands, TICK)
+    }
+}
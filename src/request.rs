@@ -1,58 +1,207 @@
-use std::{error::Error, env};
+use std::{error::Error, env, fmt, time::Duration};
 
-use reqwest::Client;
+use reqwest::{Client, RequestBuilder, Response, StatusCode};
+use serde::{Serialize, Deserialize};
 
-use crate::system::SystemPlayer;
+use crate::system::{SystemPlayer, SubmitResponse, Period, ScorePage};
 
+pub const DEFAULT_URL: &str = "http://127.0.1.0:3000";
+
+/// How to reach the score server, the `server` section of `data/config.json`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct ClientConfig {
+    pub url: String,
+    /// the `API_KEY` environment variable is used when this is left out
+    pub api_key: Option<String>,
+    /// milliseconds before a request is given up on
+    pub timeout_ms: u64,
+    /// extra attempts after a request couldn't reach the server
+    pub retries: u32,
+    /// milliseconds before the first retry, doubled for every retry after it
+    pub backoff_ms: u64
+}
+
+impl Default for ClientConfig {
+    fn default() -> Self {
+        Self { url: DEFAULT_URL.to_string(), api_key: None, timeout_ms: 5000, retries: 2, backoff_ms: 250 }
+    }
+}
+
+/// Why talking to the score server failed, `Display` gives the message shown to the player.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LeaderboardError {
+    /// the server couldn't be reached at all
+    Network,
+    Timeout,
+    /// 401, the API key is missing or wrong
+    Unauthorized,
+    /// 429, too many requests
+    RateLimited,
+    /// 5xx with the status code
+    ServerError(u16),
+    /// the response wasn't what the protocol says, with the reason
+    Decode(String),
+    /// the server refused the request and said why
+    Server(String)
+}
+
+impl LeaderboardError {
+    /**
+     * desc: true when trying again later could work, e.g. to queue a score for the next sync
+     */
+    pub fn is_temporary(&self) -> bool {
+        matches!(self, LeaderboardError::Network | LeaderboardError::Timeout | LeaderboardError::RateLimited | LeaderboardError::ServerError(_))
+    }
+    /**
+     * desc: true when a score that couldn't be submitted should wait for the next sync,
+     * a rejected API key can still be fixed, a score the server refused can't
+     */
+    pub fn should_queue(&self) -> bool {
+        self.is_temporary() || *self == LeaderboardError::Unauthorized
+    }
+}
+
+impl fmt::Display for LeaderboardError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LeaderboardError::Network => write!(f, "Couldn't Connect To Server"),
+            LeaderboardError::Timeout => write!(f, "Server Took Too Long To Answer"),
+            LeaderboardError::Unauthorized => write!(f, "Server Rejected The API Key"),
+            LeaderboardError::RateLimited => write!(f, "Too Many Requests, Try Again Soon"),
+            LeaderboardError::ServerError(status) => write!(f, "Server Error {}, Try Again Later", status),
+            LeaderboardError::Decode(_) => write!(f, "Server Sent Something Unexpected"),
+            LeaderboardError::Server(message) => write!(f, "{}", message)
+        }
+    }
+}
+
+impl Error for LeaderboardError {}
+
+impl From<reqwest::Error> for LeaderboardError {
+    fn from(e: reqwest::Error) -> Self {
+        if e.is_timeout() {
+            LeaderboardError::Timeout
+        } else if e.is_decode() || e.is_body() {
+            LeaderboardError::Decode(e.to_string())
+        } else {
+            LeaderboardError::Network
+        }
+    }
+}
+
+#[derive(Clone)]
 pub struct ReqClient {
     client: Client,
     api_key: String,
-    url: String
+    url: String,
+    retries: u32,
+    backoff: Duration
 }
 
 impl ReqClient {
     pub fn new() -> Self {
-        let api_key = match env::var("API_KEY") {
-            Ok(key) => key,
-            Err(_) => String::new()
-        };
-        // let url = "https://invaders-server.herokuapp.com/".to_string();
-        let url = "http://127.0.1.0:3000".to_string();
-        Self { client: Client::new(), api_key, url}
-    }
-    pub async fn get_scores(&self) -> Result<Vec<SystemPlayer>, Box<dyn Error>> {
-        let response = match self.client.get(self.url.as_str())
-        .header("x-api-key", self.api_key.as_str())
-        .send()
-        .await {
-            Ok(res) => res,
-            Err(_) => return Err("Couldn't Connect To Server".into()) 
-        };
-    
-        let body = response.text().await.unwrap();
-
-        let high_scores = match serde_json::from_str::<Vec<SystemPlayer>>(&body) {
-            Ok(scores) => scores,
-            Err(_) => return Err(body.into())
-        };
-        Ok(high_scores)
+        Self::from_config(ClientConfig::default())
+    }
+    pub fn with_url(url: String) -> Self {
+        Self::from_config(ClientConfig { url, ..Default::default() })
+    }
+    pub fn from_config(config: ClientConfig) -> Self {
+        let api_key = config.api_key.unwrap_or_else(|| env::var("API_KEY").unwrap_or_default());
+        let client = Client::builder()
+            .timeout(Duration::from_millis(config.timeout_ms))
+            .build()
+            .unwrap_or_default();
+        Self { client, api_key, url: config.url, retries: config.retries, backoff: Duration::from_millis(config.backoff_ms) }
+    }
+    /**
+     * desc: uses `api_key` instead of the configured one
+     */
+    pub fn with_key(mut self, api_key: String) -> Self {
+        self.api_key = api_key;
+        self
     }
-    pub async fn update_scores(&self, score: SystemPlayer) -> Result<String, Box<dyn Error>> {
+    /**
+     * desc: sends the request made by `request` and returns the body of a successful response,
+     * retrying up to `retries` times with backoff while the failure looks temporary
+     */
+    async fn send(&self, request: impl Fn() -> RequestBuilder, retries: u32) -> Result<String, LeaderboardError> {
+        let mut backoff = self.backoff;
+        let mut attempt = 0;
+        loop {
+            let result = match request().header("x-api-key", self.api_key.as_str()).send().await {
+                Ok(response) => Self::read(response).await,
+                Err(e) => Err(LeaderboardError::from(e))
+            };
+            match result {
+                Err(e) if e.is_temporary() && attempt < retries => {
+                    tokio::time::sleep(backoff).await;
+                    backoff *= 2;
+                    attempt += 1;
+                }
+                result => return result
+            }
+        }
+    }
+    /**
+     * desc: the body of a successful response, or the error its status code stands for
+     */
+    async fn read(response: Response) -> Result<String, LeaderboardError> {
+        let status = response.status();
+        let body = response.text().await?;
+        match status {
+            status if status.is_success() => Ok(body),
+            StatusCode::UNAUTHORIZED => Err(LeaderboardError::Unauthorized),
+            StatusCode::TOO_MANY_REQUESTS => Err(LeaderboardError::RateLimited),
+            status if status.is_server_error() => Err(LeaderboardError::ServerError(status.as_u16())),
+            status if body.trim().is_empty() => Err(LeaderboardError::Server(status.to_string())),
+            _ => Err(LeaderboardError::Server(body.trim().to_string()))
+        }
+    }
+    pub async fn get_scores(&self) -> Result<Vec<SystemPlayer>, LeaderboardError> {
+        let body = self.send(|| self.client.get(self.url.as_str()), self.retries).await?;
+        match serde_json::from_str::<Vec<SystemPlayer>>(&body) {
+            Ok(scores) => Ok(scores),
+            Err(e) => Err(LeaderboardError::Decode(e.to_string()))
+        }
+    }
+    /**
+     * desc: one page of the leaderboard for `period`, `around` asks for the page with that player on it instead of `offset`
+     */
+    pub async fn get_page(&self, period: Period, offset: usize, limit: usize, around: Option<&str>) -> Result<ScorePage, LeaderboardError> {
+        let url = format!("{}/scores", self.url.trim_end_matches('/'));
+        let mut query = vec![("period", period.as_str().to_string()), ("offset", offset.to_string()), ("limit", limit.to_string())];
+        if let Some(name) = around {
+            query.push(("around", name.to_string()));
+        }
+        let body = self.send(|| self.client.get(url.as_str()).query(&query), self.retries).await?;
+        match serde_json::from_str::<ScorePage>(&body) {
+            Ok(page) => Ok(page),
+            Err(e) => Err(LeaderboardError::Decode(e.to_string()))
+        }
+    }
+    /**
+     * desc: submits a score. Never retried, a submission
+     * that timed out may still have been stored and sending it again would store it twice,
+     * a failed one is queued for the next sync instead
+     */
+    pub async fn update_scores(&self, score: SystemPlayer) -> Result<SubmitResponse, LeaderboardError> {
         let body = match serde_json::to_string(&score) {
             Ok(data) => data,
-            Err(_) => return Err("There Was A Problem Parsing Score Data".into())
+            Err(e) => return Err(LeaderboardError::Decode(e.to_string()))
         };
-        let response = match self.client.post(self.url.as_str())
+        let body = self.send(|| self.client.post(self.url.as_str())
             .header("Content-Type", "application/json")
-            .header("x-api-key", self.api_key.as_str())
-            .body(body)
-            .send()
-            .await {
-            Ok(res) => res,
-            Err(_) => return Err("Couldn't Connect To Server".into())  
-        };
-        let body = response.text().await.unwrap();
+            .body(body.clone()), 0).await?;
+        match serde_json::from_str::<SubmitResponse>(&body) {
+            Ok(response) => Ok(response),
+            Err(e) => Err(LeaderboardError::Decode(e.to_string()))
+        }
+    }
+}
 
-        Ok(body)
+impl Default for ReqClient {
+    fn default() -> Self {
+        Self::new()
     }
-}
\ No newline at end of file
+}
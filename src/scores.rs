@@ -0,0 +1,161 @@
+use std::{fs, error::Error, io::ErrorKind, path::Path, time::{SystemTime, UNIX_EPOCH}};
+use serde::{Serialize, Deserialize};
+
+use crate::{proof::ScoreProof, system::{SystemPlayer, Period, RankedScore, ScorePage}};
+
+pub const SCORES_PATH: &str = "data/scores.json";
+/// scores kept on the local leaderboard
+pub const TOP_SCORES: usize = 20;
+
+/// A finished game as kept on this machine.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct LocalScore {
+    pub name: String,
+    pub score: u32,
+    /// level reached, 0 for scores saved before levels were recorded
+    #[serde(default)]
+    pub level: u128,
+    /// unix time the game ended
+    #[serde(default)]
+    pub date: u64,
+    /// proof of the game, only kept while the score waits for the server
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proof: Option<ScoreProof>
+}
+
+impl LocalScore {
+    pub fn new(name: String, score: u32, level: u128) -> Self {
+        Self { name, score, level, date: now(), proof: None }
+    }
+    pub fn with_proof(mut self, proof: ScoreProof) -> Self {
+        self.proof = Some(proof);
+        self
+    }
+    pub fn player(&self) -> SystemPlayer {
+        SystemPlayer { name: self.name.clone(), score: self.score, is: "score".to_string(), proof: self.proof.clone() }
+    }
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct ScoreFile {
+    scores: Vec<LocalScore>,
+    /// scores the server hasn't seen yet, oldest first
+    #[serde(default)]
+    pending: Vec<LocalScore>
+}
+
+/// Local leaderboard for when the score server can't be reached, and the queue of
+/// scores waiting to be sent to it.
+#[derive(Debug, Clone, Default)]
+pub struct ScoreStore {
+    path: String,
+    /// best first, at most `TOP_SCORES` long
+    pub scores: Vec<LocalScore>,
+    pub pending: Vec<LocalScore>
+}
+
+impl ScoreStore {
+    /**
+     * desc: reads the store at `path`, a missing file gives an empty store
+     */
+    pub fn load(path: &str) -> Result<ScoreStore, Box<dyn Error>> {
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(ScoreStore { path: path.to_string(), ..Default::default() }),
+            Err(e) => return Err(Box::new(e))
+        };
+        // older score files were a plain list of scores
+        let file = match serde_json::from_str::<serde_json::Value>(&contents) {
+            Ok(value) if value.is_array() => serde_json::from_value(value).map(|scores| ScoreFile { scores, pending: Vec::new() }),
+            Ok(value) => serde_json::from_value(value),
+            Err(e) => Err(e)
+        };
+        let file = match file {
+            Ok(file) => file,
+            Err(e) => return Err(format!("Invalid Score File {}: {}", path, e).into())
+        };
+        let mut store = ScoreStore { path: path.to_string(), scores: file.scores, pending: file.pending };
+        store.rank();
+        Ok(store)
+    }
+    pub fn save(&self) -> Result<(), Box<dyn Error>> {
+        let file = ScoreFile { scores: self.scores.clone(), pending: self.pending.clone() };
+        write_atomic(&self.path, &serde_json::to_string_pretty(&file)?)
+    }
+    /**
+     * desc: adds a finished game to the leaderboard, `queue` also keeps it for the server
+     */
+    pub fn record(&mut self, mut score: LocalScore, queue: bool) -> Result<(), Box<dyn Error>> {
+        if queue {
+            self.pending.push(score.clone());
+        }
+        score.proof = None;
+        self.scores.push(score);
+        self.rank();
+        self.save()
+    }
+    /**
+     * desc: forgets the oldest `count` queued scores once the server has them
+     */
+    pub fn synced(&mut self, count: usize) -> Result<(), Box<dyn Error>> {
+        self.pending.drain(..count.min(self.pending.len()));
+        self.save()
+    }
+    /**
+     * desc: a page of the games played since the start of `period` at unix time `now`, see `ScorePage::slice`
+     */
+    pub fn page(&self, period: Period, offset: usize, limit: usize, around: Option<&str>, now: u64) -> Result<ScorePage, String> {
+        let since = period.since(now);
+        let ranked = self.scores.iter()
+            .filter(|s| s.date >= since)
+            .enumerate()
+            .map(|(i, s)| RankedScore { rank: i + 1, name: s.name.clone(), score: s.score, date: s.date })
+            .collect();
+        ScorePage::slice(ranked, period, offset, limit, around)
+    }
+    /**
+     * desc: sorts best first, earlier games win ties, and drops anything past `TOP_SCORES`
+     */
+    fn rank(&mut self) {
+        self.scores.sort_by_key(|s| (std::cmp::Reverse(s.score), s.date));
+        self.scores.truncate(TOP_SCORES);
+    }
+}
+
+/**
+ * desc: writes a temporary file next to `path` and moves it over the old one,
+ * so a crash mid write never leaves a half written file
+ */
+pub fn write_atomic(path: &str, contents: &str) -> Result<(), Box<dyn Error>> {
+    if let Some(dir) = Path::new(path).parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let tmp = format!("{}.tmp", path);
+    fs::write(&tmp, contents)?;
+    fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+/**
+ * desc: the current unix time
+ */
+pub fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+/**
+ * desc: `YYYY-MM-DD` in UTC for a unix time
+ */
+pub fn format_date(secs: u64) -> String {
+    // days to civil date, from Howard Hinnant's date algorithms
+    let days = (secs / 86400) as i64 + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days.rem_euclid(146097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
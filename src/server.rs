@@ -0,0 +1,289 @@
+use std::{fs, error::Error, io::ErrorKind, collections::{HashMap, HashSet}, convert::Infallible, future::Future, net::SocketAddr, sync::{Arc, Mutex}};
+use hyper::{Body, Request, Response, Server, Method, StatusCode, body::HttpBody, header::CONTENT_LENGTH, service::{make_service_fn, service_fn}};
+use serde::{Serialize, Deserialize};
+use tokio::sync::Semaphore;
+
+use crate::{proof, invaders::SpeedCurve, level::Level, scores::{now, write_atomic}, system::{SystemPlayer, SubmitResponse, Period, RankedScore, ScorePage}};
+
+pub const SERVER_ADDR: &str = "127.0.1.0:3000";
+pub const SERVER_DATA: &str = "data/server_scores.json";
+/// scores on a page when the query doesn't say
+pub const PAGE_LIMIT: usize = 10;
+/// most scores a single page can ask for
+pub const MAX_PAGE_LIMIT: usize = 100;
+/// largest submission read, room for the input log of the longest game a proof can cover
+pub const MAX_BODY: usize = 4 * 1024 * 1024;
+/// games played back at once, submissions past it are turned away until one finishes
+pub const MAX_VERIFICATIONS: usize = 4;
+
+/// A submitted score, the store keeps each name's best for every period that's still running, see `prune`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct StoredScore {
+    name: String,
+    score: u32,
+    /// unix time it was submitted, 0 for scores from before dates were kept
+    #[serde(default)]
+    date: u64
+}
+
+/// Score server speaking the protocol `ReqClient` expects: `GET /` lists the scores,
+/// `GET /scores` pages through them and `POST /` submits a `SystemPlayer`, all behind the `x-api-key` header.
+/// A submission's proof is checked against the server's own levels and the default speed curve,
+/// scores without one are refused unless the server allows them.
+struct ScoreServer {
+    path: String,
+    api_key: String,
+    /// takes scores sent without a proof, e.g. from older clients, anyone with the API key can then send any score
+    allow_unproven: bool,
+    /// levels proofs are played back with
+    levels: Vec<Level>,
+    /// one permit per game being played back, see `MAX_VERIFICATIONS`
+    verifications: Arc<Semaphore>,
+    scores: Mutex<Vec<StoredScore>>
+}
+
+/**
+ * desc: index of the best score per name set since `since`, earlier scores win ties
+ */
+fn best_per_name(scores: &[StoredScore], since: u64) -> HashMap<&str, usize> {
+    let mut best: HashMap<&str, usize> = HashMap::new();
+    for (i, score) in scores.iter().enumerate().filter(|(_, s)| s.date >= since) {
+        let entry = best.entry(score.name.as_str()).or_insert(i);
+        if score.score > scores[*entry].score {
+            *entry = i;
+        }
+    }
+    best
+}
+
+/**
+ * desc: best score per name set since `since`, best first, earlier scores win ties
+ */
+fn rank(scores: &[StoredScore], since: u64) -> Vec<RankedScore> {
+    let mut best: Vec<&StoredScore> = best_per_name(scores, since).into_values().map(|i| &scores[i]).collect();
+    best.sort_by(|a, b| b.score.cmp(&a.score).then(a.date.cmp(&b.date)).then(a.name.cmp(&b.name)));
+    best.into_iter().enumerate()
+        .map(|(i, s)| RankedScore { rank: i + 1, name: s.name.clone(), score: s.score, date: s.date })
+        .collect()
+}
+
+/**
+ * desc: drops the scores that can't rank on any board any more at unix time `now`,
+ * only a name's best of all time, of this week and of today can, so the store stays a few per player
+ */
+fn prune(scores: &mut Vec<StoredScore>, now: u64) {
+    let keep: HashSet<usize> = [Period::All, Period::Week, Period::Today].iter()
+        .flat_map(|period| best_per_name(scores, period.since(now)).into_values())
+        .collect();
+    let mut i = 0;
+    scores.retain(|_| {
+        i += 1;
+        keep.contains(&(i - 1))
+    });
+}
+
+impl ScoreServer {
+    /**
+     * desc: adds the score to the store, returns where the player stands now
+     */
+    fn submit(&self, player: SystemPlayer) -> Result<SubmitResponse, Box<dyn Error>> {
+        let mut scores = self.scores.lock().unwrap();
+        let previous_best = scores.iter().filter(|s| s.name == player.name).map(|s| s.score).max();
+        let now = now();
+        scores.push(StoredScore { name: player.name.clone(), score: player.score, date: now });
+        prune(&mut scores, now);
+        write_atomic(&self.path, &serde_json::to_string(&*scores)?)?;
+        let ranked = rank(&scores, 0);
+        let rank = ranked.iter().position(|s| s.name == player.name).unwrap_or(0) + 1;
+        Ok(SubmitResponse {
+            score: player.score,
+            rank,
+            total: ranked.len(),
+            previous_best,
+            new_high_score: previous_best.is_none_or(|best| player.score > best)
+        })
+    }
+    /**
+     * desc: the all time leaderboard as the original protocol lists it
+     */
+    fn list(&self) -> Vec<SystemPlayer> {
+        rank(&self.scores.lock().unwrap(), 0).into_iter()
+            .map(|s| SystemPlayer { name: s.name, score: s.score, is: "score".to_string(), proof: None })
+            .collect()
+    }
+    fn page(&self, period: Period, offset: usize, limit: usize, around: Option<&str>) -> Result<ScorePage, String> {
+        let ranked = rank(&self.scores.lock().unwrap(), period.since(now()));
+        ScorePage::slice(ranked, period, offset, limit, around)
+    }
+}
+
+/**
+ * desc: decodes `+` and `%XX` escapes in a query string value
+ */
+fn decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => decoded.push(b' '),
+            b'%' if i + 2 < bytes.len() => {
+                let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).ok().and_then(|hex| u8::from_str_radix(hex, 16).ok());
+                match hex {
+                    Some(byte) => {
+                        decoded.push(byte);
+                        i += 2;
+                    }
+                    None => decoded.push(b'%')
+                }
+            }
+            byte => decoded.push(byte)
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).to_string()
+}
+
+fn query(req: &Request<Body>) -> HashMap<String, String> {
+    req.uri().query().unwrap_or_default().split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .map(|(key, value)| (decode(key), decode(value)))
+        .collect()
+}
+
+/**
+ * desc: the whole request body, errors with the status to answer when it's longer than `MAX_BODY` or breaks off
+ */
+async fn read_body(req: Request<Body>) -> Result<Vec<u8>, StatusCode> {
+    let too_large = req.headers().get(CONTENT_LENGTH)
+        .and_then(|length| length.to_str().ok())
+        .and_then(|length| length.parse::<usize>().ok())
+        .is_some_and(|length| length > MAX_BODY);
+    if too_large {
+        return Err(StatusCode::PAYLOAD_TOO_LARGE);
+    }
+    // a chunked body has no length up front, so it's counted as it comes in
+    let mut body = req.into_body();
+    let mut bytes = Vec::new();
+    while let Some(chunk) = body.data().await {
+        let chunk = chunk.map_err(|_| StatusCode::BAD_REQUEST)?;
+        if bytes.len() + chunk.len() > MAX_BODY {
+            return Err(StatusCode::PAYLOAD_TOO_LARGE);
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+    Ok(bytes)
+}
+
+fn respond(status: StatusCode, body: String) -> Response<Body> {
+    let mut response = Response::new(Body::from(body));
+    *response.status_mut() = status;
+    response
+}
+
+async fn handle(server: Arc<ScoreServer>, req: Request<Body>) -> Result<Response<Body>, Infallible> {
+    if !server.api_key.is_empty() {
+        let key = req.headers().get("x-api-key").and_then(|key| key.to_str().ok()).unwrap_or_default();
+        if key != server.api_key {
+            return Ok(respond(StatusCode::UNAUTHORIZED, "Invalid API Key".to_string()));
+        }
+    }
+    match (req.method(), req.uri().path()) {
+        (&Method::GET, "/") => {
+            let body = serde_json::to_string(&server.list()).unwrap_or_default();
+            Ok(respond(StatusCode::OK, body))
+        }
+        (&Method::GET, "/scores") => {
+            let query = query(&req);
+            let period = match query.get("period").map(|p| Period::parse(p)) {
+                None => Period::All,
+                Some(Some(period)) => period,
+                Some(None) => return Ok(respond(StatusCode::BAD_REQUEST, "Invalid Period".to_string()))
+            };
+            let offset = query.get("offset").and_then(|o| o.parse().ok()).unwrap_or(0);
+            let limit = query.get("limit").and_then(|l| l.parse().ok()).unwrap_or(PAGE_LIMIT).clamp(1, MAX_PAGE_LIMIT);
+            match server.page(period, offset, limit, query.get("around").map(|name| name.as_str())) {
+                Ok(page) => Ok(respond(StatusCode::OK, serde_json::to_string(&page).unwrap_or_default())),
+                Err(message) => Ok(respond(StatusCode::NOT_FOUND, message))
+            }
+        }
+        (&Method::POST, "/") => {
+            let body = match read_body(req).await {
+                Ok(body) => body,
+                Err(StatusCode::PAYLOAD_TOO_LARGE) => return Ok(respond(StatusCode::PAYLOAD_TOO_LARGE, "Submission Too Large".to_string())),
+                Err(status) => return Ok(respond(status, "Couldn't Read Request".to_string()))
+            };
+            let mut player = match serde_json::from_slice::<SystemPlayer>(&body) {
+                Ok(player) if player.is == "score" && !player.name.is_empty() => player,
+                _ => return Ok(respond(StatusCode::BAD_REQUEST, "Invalid Score Data".to_string()))
+            };
+            if player.proof.is_some() || !server.allow_unproven {
+                // a busy server is a temporary error, the client queues the score and tries again later
+                let permit = match Arc::clone(&server.verifications).try_acquire_owned() {
+                    Ok(permit) => permit,
+                    Err(_) => return Ok(respond(StatusCode::SERVICE_UNAVAILABLE, "Too Many Scores Being Checked".to_string()))
+                };
+                // playing the game back takes a while, so it runs off the async workers
+                let checker = Arc::clone(&server);
+                let checked = tokio::task::spawn_blocking(move || {
+                    let result = proof::verify(&player, &checker.levels, SpeedCurve::default());
+                    drop(permit);
+                    (player, result)
+                }).await;
+                player = match checked {
+                    Ok((player, Ok(()))) => player,
+                    Ok((_, Err(e))) => return Ok(respond(StatusCode::UNPROCESSABLE_ENTITY, format!("Score Rejected: {}", e))),
+                    Err(_) => return Ok(respond(StatusCode::UNPROCESSABLE_ENTITY, "Score Rejected: Game Couldn't Be Played Back".to_string()))
+                };
+            }
+            match server.submit(player) {
+                Ok(response) => Ok(respond(StatusCode::OK, serde_json::to_string(&response).unwrap_or_default())),
+                Err(e) => Ok(respond(StatusCode::INTERNAL_SERVER_ERROR, format!("Couldn't Save Score: {}", e)))
+            }
+        }
+        _ => Ok(respond(StatusCode::NOT_FOUND, "Not Found".to_string()))
+    }
+}
+
+/**
+ * desc: binds a score server for the scores stored in `path` without starting it,
+ * returns the address it listens on (useful with port 0) and the future that serves requests,
+ * an empty `api_key` accepts every request, `allow_unproven` takes scores sent without a proof
+ * and proofs are played back with `levels`
+ */
+pub fn bind(addr: &str, path: &str, api_key: String, allow_unproven: bool, levels: Vec<Level>) -> Result<(SocketAddr, impl Future<Output = hyper::Result<()>>), Box<dyn Error>> {
+    let addr: SocketAddr = match addr.parse() {
+        Ok(addr) => addr,
+        Err(_) => return Err(format!("Invalid Address: {}", addr).into())
+    };
+    // a store that isn't there yet starts empty, one that can't be read mustn't be overwritten
+    let scores = match fs::read_to_string(path) {
+        Ok(contents) => serde_json::from_str(&contents)?,
+        Err(e) if e.kind() == ErrorKind::NotFound => Vec::new(),
+        Err(e) => return Err(format!("Couldn't Read Score File {}: {}", path, e).into())
+    };
+    let server = Arc::new(ScoreServer {
+        path: path.to_string(),
+        api_key,
+        allow_unproven,
+        levels,
+        verifications: Arc::new(Semaphore::new(MAX_VERIFICATIONS)),
+        scores: Mutex::new(scores)
+    });
+    let make_service = make_service_fn(move |_| {
+        let server = Arc::clone(&server);
+        async move { Ok::<_, Infallible>(service_fn(move |req| handle(Arc::clone(&server), req))) }
+    });
+    let server = Server::try_bind(&addr)?.serve(make_service);
+    Ok((server.local_addr(), server))
+}
+
+/**
+ * desc: serves scores stored in `path` on `addr` until the process is stopped, see `bind`
+ */
+pub async fn run(addr: &str, path: &str, api_key: String, allow_unproven: bool, levels: Vec<Level>) -> Result<(), Box<dyn Error>> {
+    let (addr, server) = bind(addr, path, api_key, allow_unproven, levels)?;
+    println!("Score Server Listening On http://{}", addr);
+    server.await?;
+    Ok(())
+}
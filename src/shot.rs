@@ -1,25 +1,52 @@
 use std::time::Duration;
 
+use crossterm::style::{Color, Attribute};
 use rusty_time::timer::Timer;
 
-use crate::{frame::Drawable};
+use crate::{frame::{Drawable, Cell}, Playfield};
+
+#[derive(PartialEq, Clone, Copy)]
+pub enum ShotDirection {
+    Up,
+    Down
+}
 
 pub struct Shot {
     pub x: usize,
     pub y: usize,
     pub exploding: bool,
+    pub direction: ShotDirection,
+    field: Playfield,
+    gone: bool,
     timer: Timer,
 }
 
 impl Shot {
-    pub fn new(x: usize, y: usize) -> Self {
-        Self { x, y, exploding: false, timer: Timer::from_millis(50) }
+    pub fn new(x: usize, y: usize, direction: ShotDirection, field: Playfield) -> Self {
+        let timer = match direction {
+            ShotDirection::Up => Timer::from_millis(50),
+            ShotDirection::Down => Timer::from_millis(100)
+        };
+        Self { x, y, exploding: false, direction, field, gone: false, timer }
     }
     pub fn update(&mut self, delta: Duration) {
         self.timer.update(delta);
         if self.timer.ready && !self.exploding {
-            if self.y > 0 {
-                self.y -= 1;
+            match self.direction {
+                ShotDirection::Up => {
+                    if self.y > 0 {
+                        self.y -= 1;
+                    } else {
+                        self.gone = true;
+                    }
+                }
+                ShotDirection::Down => {
+                    if self.y < self.field.rows - 1 {
+                        self.y += 1;
+                    } else {
+                        self.gone = true;
+                    }
+                }
             }
             self.timer.reset();
         }
@@ -29,12 +56,19 @@ impl Shot {
         self.timer = Timer::from_millis(250);
     }
     pub fn dead(&self) -> bool {
-        (self.exploding && self.timer.ready) || self.y == 0
+        (self.exploding && self.timer.ready) || self.gone
     }
 }
 
 impl Drawable for Shot {
     fn draw(&self, frame: &mut crate::frame::Frame) {
-        frame[self.x][self.y] = if self.exploding {"*".to_string()} else {"|".to_string()};
+        frame[self.x][self.y] = if self.exploding {
+            Cell::new('*').fg(Color::Yellow).attribute(Attribute::Bold)
+        } else {
+            match self.direction {
+                ShotDirection::Up => Cell::new('|').fg(Color::White),
+                ShotDirection::Down => Cell::new('!').fg(Color::Red)
+            }
+        };
     }
-}
\ No newline at end of file
+}
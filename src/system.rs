@@ -1,29 +1,150 @@
-use std::{fs::{File}, error::Error, io::{Read, self, BufReader}, collections::HashMap, thread::{JoinHandle, self}, sync::{mpsc::{Sender, self}, Arc, Mutex}};
-use rodio::{Decoder,Sink, OutputStreamHandle};
+use std::{io, fmt};
 use serde::{Serialize, Deserialize};
-use serde_json;
 
-pub const AUDIO_THREAD_COUNT: u32 = 4;
-
-use crate::{menu::Menu};
+use crate::{menu::Menu, scores::ScoreStore, proof::ScoreProof};
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct SystemPlayer {
     pub name: String,
     pub score: u32,
-    pub is: String
+    pub is: String,
+    /// sent with submissions so the server can check the score, see `proof::verify`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proof: Option<ScoreProof>
 }
 
 impl SystemPlayer {
     pub fn new(name: String) -> Self {
-        Self { name, score: 0, is: "score".to_string() }
+        Self { name, score: 0, is: "score".to_string(), proof: None }
+    }
+}
+
+/// What the score server answers to a submitted score.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SubmitResponse {
+    /// the score as submitted
+    pub score: u32,
+    /// rank of the player's best score, 1 is the top
+    pub rank: usize,
+    /// players on the leaderboard
+    pub total: usize,
+    /// best score before this one, `None` for a player's first score
+    pub previous_best: Option<u32>,
+    pub new_high_score: bool
+}
+
+impl fmt::Display for SubmitResponse {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.previous_best {
+            Some(_) if self.new_high_score => write!(f, "New personal best! Rank #{} of {}", self.rank, self.total),
+            Some(best) => write!(f, "Scored {}, your best is still {}\nRank #{} of {}", self.score, best, self.rank, self.total),
+            None => write!(f, "First score on the board! Rank #{} of {}", self.rank, self.total)
+        }
+    }
+}
+
+/// Time window a leaderboard is ranked over.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Period {
+    #[default]
+    All,
+    Week,
+    Today
+}
+
+impl Period {
+    pub fn next(&self) -> Period {
+        match self {
+            Period::All => Period::Week,
+            Period::Week => Period::Today,
+            Period::Today => Period::All
+        }
+    }
+    pub fn name(&self) -> &'static str {
+        match self {
+            Period::All => "All Time",
+            Period::Week => "This Week",
+            Period::Today => "Today"
+        }
+    }
+    /**
+     * desc: the value used in leaderboard queries
+     */
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Period::All => "all",
+            Period::Week => "week",
+            Period::Today => "today"
+        }
+    }
+    pub fn parse(period: &str) -> Option<Period> {
+        [Period::All, Period::Week, Period::Today].into_iter().find(|p| p.as_str() == period)
+    }
+    /**
+     * desc: earliest unix time that counts at unix time `now`, days are UTC and weeks start on Monday
+     */
+    pub fn since(&self, now: u64) -> u64 {
+        let day = now / 86400;
+        match self {
+            Period::All => 0,
+            // 1970-01-01 was a Thursday
+            Period::Week => day.saturating_sub((day + 3) % 7) * 86400,
+            Period::Today => day * 86400
+        }
+    }
+}
+
+/// A line of the leaderboard.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RankedScore {
+    /// 1 is the top
+    pub rank: usize,
+    pub name: String,
+    pub score: u32,
+    /// unix time the score was set, 0 when unknown
+    #[serde(default)]
+    pub date: u64
+}
+
+/// Part of a leaderboard, `GET /scores?offset=&limit=&period=&around=` on the score server.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct ScorePage {
+    pub period: Period,
+    /// index of the first score on the page
+    pub offset: usize,
+    /// scores on the whole leaderboard
+    pub total: usize,
+    pub scores: Vec<RankedScore>
+}
+
+impl ScorePage {
+    /**
+     * desc: up to `limit` of the `ranked` scores from `offset`, or from the page with
+     * `around` on it, errors when `around` isn't on the leaderboard
+     */
+    pub fn slice(ranked: Vec<RankedScore>, period: Period, offset: usize, limit: usize, around: Option<&str>) -> Result<ScorePage, String> {
+        let limit = limit.max(1);
+        let offset = match around {
+            Some(name) => match ranked.iter().position(|s| s.name == name) {
+                Some(i) => i / limit * limit,
+                None => return Err(format!("{} Isn't On This Leaderboard", name))
+            },
+            None => offset
+        };
+        // scrolling past the end stops at the last full page
+        let offset = offset.min(ranked.len().saturating_sub(limit));
+        let total = ranked.len();
+        let scores = ranked.into_iter().skip(offset).take(limit).collect();
+        Ok(ScorePage { period, offset, total, scores })
     }
 }
 
 pub struct System {
     pub menu: Menu,
     pub player: SystemPlayer,
-    pub high_scores: Vec<SystemPlayer>,
+    /// local leaderboard, see `ScoreStore::load`
+    pub scores: ScoreStore,
 }
 
 impl System {
@@ -31,26 +152,9 @@ impl System {
         Self { 
             menu: Menu::Main, player: 
             SystemPlayer::new("new player".to_string()), 
-            high_scores: Vec::new(),
+            scores: ScoreStore::default(),
         }
     }
-    pub fn read_data(&mut self) -> Result<bool, Box<dyn Error>> {
-        let mut file = match File::open("data/scores.json") {
-            Ok(file) => file,
-            Err(e) => return Err(Box::new(e)),
-        };
-        let mut contents = String::new();
-        match file.read_to_string(&mut contents) {
-            Ok(data) => data,
-            Err(e) => return Err(Box::new(e))
-        };
-        let data: Vec<SystemPlayer> = match serde_json::from_str(&contents) {
-            Ok(data) => data,
-            Err(e) => return Err(Box::new(e))
-        };
-        self.high_scores = data;
-        Ok(true)
-    }
     pub fn get_menu_input(&mut self) {
         let mut input = String::new();
         io::stdin().read_line(&mut input).expect("Failed To Get User Input");
@@ -65,121 +169,8 @@ impl System {
     }
 }
 
-pub struct AudioThread {
-    handle: JoinHandle<()>,
-    tx: Sender<Arc<AudioChannel>>,
-    is_playing: Arc<Mutex<bool>>
-}
-
-fn get_ready_thread(threads: &Vec<AudioThread>) -> Option<usize> {
-    for (i, t) in threads.iter().enumerate() {
-        if *t.is_playing.lock().unwrap() == false {
-            return  Some(i);
-        }
+impl Default for System {
+    fn default() -> Self {
+        Self::new()
     }
-    None
-}
-
-pub struct AudioChannel {
-    file: &'static str,
-    stop: bool
 }
-
-pub struct Audio {
-    files: HashMap<&'static str, &'static str>,
-    thread_handle: JoinHandle<()>,
-    audio_tx: Sender<AudioChannel>,
-}
-
-impl Audio {
-    pub fn new(stream: OutputStreamHandle) -> Self {
-        let (tx, rx) = mpsc::channel::<AudioChannel>();
-        let handle = thread::spawn(move || {
-            // initialize seperate threads
-            let mut threads = Vec::new();
-            for _ in 0..AUDIO_THREAD_COUNT {
-                // initilize channel
-                let (tx, rx) = mpsc::channel::<Arc<AudioChannel>>();
-                // initilize sink
-                let sink = Sink::try_new(&stream).unwrap();
-                // initilize is_playing to determine if the thread is playing a sound
-                let is_playing = Arc::new(Mutex::new(false));
-                let is_playing_clone = Arc::clone(&is_playing);
-                // initilize thread
-                let handle = thread::spawn(move || {
-                    let is_playing = is_playing_clone;
-                    loop {
-                        match rx.recv() {
-                            Ok(data) => {
-                                if data.stop {
-                                    sink.stop()
-                                }
-                                else {
-                                    let file = File::open(data.file).expect(format!("Failed To Open File {}", data.file).as_str());
-                                    let buf = BufReader::new(file);
-                                    let source: Decoder<BufReader<File>> = Decoder::new(buf).unwrap();
-                                    *is_playing.lock().unwrap() = true;
-                                    sink.append(source);
-                                    sink.sleep_until_end();
-                                    *is_playing.lock().unwrap() = false;           
-                                }
-                            }
-                            Err(_) => break
-                        }
-                    }
-                });
-                // push the data to the thread management Vector
-                threads.push(AudioThread { handle, tx, is_playing });
-            }
-            loop {
-                // thread::sleep(Duration::from_millis(1));
-                match rx.recv() {
-                    Ok(data) => {
-                        let data = Arc::new(data);
-                        if data.stop {
-                            for t in threads.iter() {
-                                let data = Arc::clone(&data);
-                                let _ = t.tx.send(data);
-                            }
-                        }
-                        else {
-                            // println!("hit spacebar");
-                            if let Some(i) = get_ready_thread(&threads) {
-                                let t = threads.get(i).unwrap();
-                                let _ = t.tx.send(data);
-                                
-                            }
-                        }
-                    },
-                    Err(_) => break
-                }
-            }
-            for t in threads.into_iter() {
-                drop(t.tx);
-                t.handle.join().unwrap();
-            }
-        });
-        Self { 
-            files: HashMap::new(),
-            thread_handle: handle,
-            audio_tx: tx,
-        }
-    }
-
-    pub fn add(&mut self, name: &'static str, path: &'static str) {
-        self.files.insert(name, path);
-    }
-
-    pub fn play(&mut self, key: &'static str) {
-        let file = self.files.get(key).expect("Invalid Audio Name");
-        let _ = self.audio_tx.send(AudioChannel { file: file, stop: false });
-    }
-
-    pub fn stop(&mut self) {
-        let _ = self.audio_tx.send(AudioChannel { file: "nothing", stop: true });
-    }
-    pub fn close(self) {
-        drop(self.audio_tx);
-        self.thread_handle.join().unwrap();
-    }
-}
\ No newline at end of file
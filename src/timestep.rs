@@ -0,0 +1,79 @@
+use std::time::{Duration, Instant};
+
+/// simulation rate, every `Game::update` advances the game by exactly one `TICK`
+pub const TICK_RATE: u32 = 60;
+pub const TICK: Duration = Duration::from_nanos(1_000_000_000 / TICK_RATE as u64);
+/// most ticks simulated in one go after a stall, the rest is dropped
+pub const MAX_CATCH_UP: u32 = 10;
+pub const DEFAULT_FPS: u32 = 60;
+
+/// Accumulates real time and hands it out in fixed `TICK`s.
+pub struct FixedTimestep {
+    accumulator: Duration,
+    last: Instant
+}
+
+impl FixedTimestep {
+    pub fn new() -> Self {
+        Self { accumulator: Duration::ZERO, last: Instant::now() }
+    }
+    /**
+     * desc: forgets the time passed so far, e.g. after a pause
+     */
+    pub fn reset(&mut self) {
+        self.accumulator = Duration::ZERO;
+        self.last = Instant::now();
+    }
+    /**
+     * desc: number of ticks due since the last call
+     */
+    pub fn advance(&mut self) -> u32 {
+        let now = Instant::now();
+        self.accumulator += now - self.last;
+        self.last = now;
+        let mut ticks = 0;
+        while self.accumulator >= TICK {
+            self.accumulator -= TICK;
+            ticks += 1;
+        }
+        if ticks > MAX_CATCH_UP {
+            ticks = MAX_CATCH_UP;
+        }
+        ticks
+    }
+}
+
+impl Default for FixedTimestep {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Caps how often frames are drawn, independently of the simulation rate.
+pub struct FrameLimiter {
+    interval: Duration,
+    last: Option<Instant>
+}
+
+impl FrameLimiter {
+    /**
+     * desc: limiter for `fps` frames a second, 0 means uncapped
+     */
+    pub fn new(fps: u32) -> Self {
+        let interval = if fps == 0 { Duration::ZERO } else { Duration::from_secs(1) / fps };
+        Self { interval, last: None }
+    }
+    /**
+     * desc: true when it's time to draw another frame
+     */
+    pub fn ready(&mut self) -> bool {
+        let now = Instant::now();
+        match self.last {
+            Some(last) if now - last < self.interval => false,
+            _ => {
+                self.last = Some(now);
+                true
+            }
+        }
+    }
+}
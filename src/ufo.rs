@@ -0,0 +1,88 @@
+use std::time::Duration;
+
+use crossterm::style::{Color, Attribute};
+use rusty_time::timer::Timer;
+
+use crate::{Direction, Playfield, frame::{Drawable, Frame, Cell}, rng::Rng};
+
+pub const UFO_WIDTH: usize = 3;
+/// possible bonus scores, one is picked at random per hit
+pub const UFO_BONUSES: [u32; 4] = [50, 100, 150, 300];
+
+pub struct Ufo {
+    pub x: usize,
+    pub direction: Direction,
+    pub active: bool,
+    field: Playfield,
+    spawn_timer: Timer,
+    move_timer: Timer
+}
+
+impl Ufo {
+    pub fn new(rng: &mut Rng, field: Playfield) -> Self {
+        Self {
+            x: 0,
+            direction: Direction::Right,
+            active: false,
+            field,
+            spawn_timer: Self::spawn_timer(rng),
+            move_timer: Timer::from_millis(150)
+        }
+    }
+    fn spawn_timer(rng: &mut Rng) -> Timer {
+        Timer::from_millis(rng.range(10_000, 25_000))
+    }
+    /**
+     * desc: flies the ufo across the top row, returns true when a new one appears
+     */
+    pub fn update(&mut self, delta: Duration, rng: &mut Rng) -> bool {
+        if !self.active {
+            self.spawn_timer.update(delta);
+            if !self.spawn_timer.ready {
+                return false;
+            }
+            self.spawn_timer = Self::spawn_timer(rng);
+            self.active = true;
+            self.move_timer.reset();
+            if rng.range(0, 2) == 0 {
+                self.direction = Direction::Right;
+                self.x = 0;
+            } else {
+                self.direction = Direction::Left;
+                self.x = self.field.cols - UFO_WIDTH;
+            }
+            return true;
+        }
+        self.move_timer.update(delta);
+        if self.move_timer.ready {
+            self.move_timer.reset();
+            match self.direction {
+                Direction::Left if self.x > 0 => self.x -= 1,
+                Direction::Right if self.x < self.field.cols - UFO_WIDTH => self.x += 1,
+                _ => self.active = false
+            }
+        }
+        false
+    }
+    /**
+     * desc: returns the bonus score if the ufo covers x, y
+     */
+    pub fn hit_at(&mut self, x: usize, y: usize, rng: &mut Rng) -> Option<u32> {
+        if !self.active || y != 0 || x < self.x || x >= self.x + UFO_WIDTH {
+            return None;
+        }
+        self.active = false;
+        Some(UFO_BONUSES[rng.range(0, UFO_BONUSES.len() as u64) as usize])
+    }
+}
+
+impl Drawable for Ufo {
+    fn draw(&self, frame: &mut Frame) {
+        if !self.active {
+            return;
+        }
+        for (i, c) in "<@>".chars().enumerate() {
+            frame[self.x + i][0] = Cell::new(c).fg(Color::Red).attribute(Attribute::Bold);
+        }
+    }
+}
@@ -0,0 +1,64 @@
+use invaders::cli::{Cli, Subcommand, PlayArgs, ServerArgs, USAGE, SERVER_USAGE};
+
+fn parse(args: &str) -> Result<Cli, String> {
+    Cli::parse(args.split_whitespace().map(String::from))
+}
+
+#[test]
+fn no_arguments_opens_the_menu() {
+    let cli = parse("").unwrap();
+    assert_eq!(cli.command, Subcommand::Play { args: PlayArgs::default(), start: false });
+    assert_eq!((cli.config, cli.server_url, cli.offline), (None, None, false));
+}
+
+#[test]
+fn parses_play_options_in_any_order() {
+    let cli = parse("--seed 9 play --level 3 --name ann --no-audio --mute --offline --config c.json").unwrap();
+    let args = PlayArgs { level: Some(3), seed: Some(9), name: Some("ann".to_string()), no_audio: true, mute: true };
+    assert_eq!(cli.command, Subcommand::Play { args, start: true });
+    assert_eq!(cli.config.as_deref(), Some("c.json"));
+    assert!(cli.offline);
+}
+
+#[test]
+fn parses_the_other_subcommands() {
+    assert_eq!(parse("leaderboard --server-url http://localhost:1").unwrap().command, Subcommand::Leaderboard);
+    assert_eq!(parse("replay game.json").unwrap().command, Subcommand::Replay("game.json".to_string()));
+    assert_eq!(parse("--replay game.json").unwrap().command, Subcommand::Replay("game.json".to_string()));
+    assert_eq!(parse("verify score.json").unwrap().command, Subcommand::Verify("score.json".to_string()));
+    let server = ServerArgs { addr: Some("0.0.0.0:80".to_string()), data: Some("s.json".to_string()), allow_unproven: true };
+    assert_eq!(parse("server --addr 0.0.0.0:80 --data s.json --allow-unproven").unwrap().command, Subcommand::Server(server));
+    assert_eq!(parse("play --help").unwrap().command, Subcommand::Help);
+}
+
+#[test]
+fn rejects_options_for_another_subcommand() {
+    assert_eq!(parse("server --level 2").unwrap_err(), "--level Doesn't Apply To server");
+    assert_eq!(parse("--addr 0.0.0.0:80").unwrap_err(), "--addr Doesn't Apply To play");
+    assert_eq!(parse("leaderboard --name ann").unwrap_err(), "--name Doesn't Apply To leaderboard");
+    assert_eq!(parse("replay game.json --offline").unwrap_err(), "--offline Doesn't Apply To replay");
+    assert_eq!(parse("verify score.json --config c.json").unwrap_err(), "--config Doesn't Apply To verify");
+}
+
+#[test]
+fn rejects_bad_values() {
+    assert_eq!(parse("play --level").unwrap_err(), "--level Needs A Value");
+    assert_eq!(parse("play --seed abc").unwrap_err(), "Invalid Value For --seed: abc");
+    assert_eq!(parse("play --level 0").unwrap_err(), "Levels Start At 1");
+    assert_eq!(parse("play leaderboard").unwrap_err(), "Unknown Argument: leaderboard");
+    assert_eq!(parse("--bogus").unwrap_err(), "Unknown Argument: --bogus");
+}
+
+#[test]
+fn the_server_binary_takes_the_server_options() {
+    let args = |line: &str| ServerArgs::parse(line.split_whitespace().map(String::from));
+    let server = ServerArgs { addr: Some("0.0.0.0:80".to_string()), data: None, allow_unproven: true };
+    assert_eq!(args("--addr 0.0.0.0:80 --allow-unproven"), Ok(Some(server)));
+    assert_eq!(args("--help"), Ok(None));
+    assert_eq!(args("--seed 1").unwrap_err(), "--seed Doesn't Apply To server");
+    // both help texts list the same server options
+    let options = USAGE.lines().skip_while(|line| !line.starts_with("  server")).skip(1).take_while(|line| !line.is_empty());
+    for option in options {
+        assert!(SERVER_USAGE.contains(option), "{}", option);
+    }
+}
@@ -0,0 +1,28 @@
+// shared by the test files, each uses only some of it
+#![allow(dead_code)]
+
+use invaders::{game::{Command, Game}, proof::ScoreProof, replay::Replay, system::SystemPlayer, timestep::TICK};
+
+/// ticks `played_game` runs for
+pub const PLAYED_TICKS: usize = 2000;
+
+/**
+ * desc: a game played headlessly for `PLAYED_TICKS`, moving back and forth and shooting on a fixed pattern
+ */
+pub fn played_game(seed: u64) -> Game {
+    let mut game = Game::with_seed(seed);
+    let pattern = [Command::MoveLeft, Command::Shoot, Command::MoveRight, Command::Shoot];
+    for tick in 0..PLAYED_TICKS {
+        let commands = if tick % 5 == 0 { vec![pattern[tick / 5 % pattern.len()]] } else { Vec::new() };
+        game.update(&commands, TICK);
+    }
+    game
+}
+
+/**
+ * desc: `played_game` submitted as `name` with its proof
+ */
+pub fn proven_player(name: &str, seed: u64) -> SystemPlayer {
+    let replay = Replay::from_game(&played_game(seed));
+    SystemPlayer { name: name.to_string(), score: replay.score, is: "score".to_string(), proof: Some(ScoreProof::from_replay(&replay)) }
+}
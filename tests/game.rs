@@ -0,0 +1,53 @@
+mod common;
+
+use invaders::{Playfield, game::{Command, Game, GameEvent, Input}, level::{Level, LEVEL_DIR}, timestep::TICK};
+
+#[test]
+fn update_applies_commands_without_a_terminal() {
+    let mut game = Game::with_seed(3);
+    game.update(&[Command::MoveLeft], TICK);
+    let events = game.update(&[Command::Shoot, Command::MoveRight], TICK);
+    assert!(events.contains(&GameEvent::Shot));
+    assert_eq!(game.tick, 2);
+    assert_eq!(game.inputs, [
+        Input { tick: 1, command: Command::MoveLeft },
+        Input { tick: 2, command: Command::Shoot },
+        Input { tick: 2, command: Command::MoveRight }
+    ]);
+}
+
+#[test]
+fn played_game_scores_and_records_every_tick() {
+    let game = common::played_game(7);
+    assert!(game.player.score > 0);
+    assert_eq!(game.tick as usize, common::PLAYED_TICKS);
+    assert_eq!(game.inputs.len(), common::PLAYED_TICKS / 5);
+}
+
+#[test]
+fn an_idle_player_loses_and_the_game_stops() {
+    let mut game = Game::with_seed(5);
+    let mut events = Vec::new();
+    while !game.over && game.tick < 1_000_000 {
+        events.extend(game.update(&[], TICK));
+    }
+    assert!(game.over);
+    assert_eq!(events.last(), Some(&GameEvent::GameOver));
+    // a finished game ignores further updates
+    let tick = game.tick;
+    assert!(game.update(&[Command::Shoot], TICK).is_empty());
+    assert_eq!(game.tick, tick);
+}
+
+#[test]
+fn generated_levels_are_harder_than_the_level_files() {
+    let levels = Level::load_dir(LEVEL_DIR).unwrap();
+    let intervals: Vec<_> = (1..=levels.len() as u128 + 2).map(|level| {
+        let mut game = Game::with_levels(1, levels.clone(), Playfield::default());
+        game.start_at(level);
+        game.invaders.move_interval()
+    }).collect();
+    for pair in intervals.windows(2) {
+        assert!(pair[1] < pair[0], "move interval went from {:?} to {:?}", pair[0], pair[1]);
+    }
+}
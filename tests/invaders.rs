@@ -0,0 +1,47 @@
+use std::time::Duration;
+
+use invaders::{Playfield, invaders::{Invaders, SpeedCurve, MOVE_INTERVAL}};
+
+/**
+ * desc: destroys the army one invader at a time, collecting the move interval at every size
+ */
+fn intervals(mut invaders: Invaders) -> Vec<Duration> {
+    let mut intervals = vec![invaders.move_interval()];
+    while invaders.army.pop().is_some() {
+        intervals.push(invaders.move_interval());
+    }
+    intervals
+}
+
+#[test]
+fn interval_shrinks_as_the_army_is_destroyed() {
+    let invaders = Invaders::new(1, Playfield::default());
+    assert!(invaders.army.len() > 1);
+    assert_eq!(invaders.move_interval(), Duration::from_millis(MOVE_INTERVAL));
+    let intervals = intervals(invaders);
+    for pair in intervals.windows(2) {
+        assert!(pair[1] < pair[0], "interval went from {:?} to {:?}", pair[0], pair[1]);
+    }
+}
+
+#[test]
+fn configured_curve_is_monotonic() {
+    for curve in [SpeedCurve { fastest: 0.2, exponent: 0.5 }, SpeedCurve { fastest: 0.0, exponent: 3.0 }] {
+        let mut invaders = Invaders::new(2, Playfield::new(60, 24));
+        invaders.curve = curve;
+        let intervals = intervals(invaders);
+        // a curve down to 0 bottoms out at the shortest interval the timer allows
+        for pair in intervals.windows(2) {
+            assert!(pair[1] <= pair[0], "{:?}: interval went from {:?} to {:?}", curve, pair[0], pair[1]);
+        }
+        assert!(intervals.last() < intervals.first());
+    }
+}
+
+#[test]
+fn constant_curve_keeps_the_pace() {
+    let mut invaders = Invaders::new(1, Playfield::default());
+    invaders.curve = SpeedCurve::constant();
+    let intervals = intervals(invaders);
+    assert!(intervals.iter().all(|interval| *interval == intervals[0]));
+}
@@ -0,0 +1,29 @@
+use invaders::{Playfield, frame::{Drawable, Frame}, menu::NewMenu};
+
+/**
+ * desc: the glyphs on row `y` of the frame, trailing blanks trimmed
+ */
+fn row(frame: &Frame, y: usize) -> String {
+    let line: String = (0..frame.cols()).map(|x| frame[x][y].glyph).collect();
+    line.trim_end().to_string()
+}
+
+#[test]
+fn draws_every_line_from_the_same_column() {
+    let mut frame = Frame::new(Playfield::default());
+    NewMenu::new("Score Not Submitted:\nCouldn't Connect\n\nRank #1 of 1".to_string(), 2, 1).draw(&mut frame);
+    assert_eq!(row(&frame, 0), "");
+    assert_eq!(row(&frame, 1), "  Score Not Submitted:");
+    assert_eq!(row(&frame, 2), "  Couldn't Connect");
+    assert_eq!(row(&frame, 3), "");
+    assert_eq!(row(&frame, 4), "  Rank #1 of 1");
+}
+
+#[test]
+fn clips_text_past_the_frame() {
+    let mut frame = Frame::new(Playfield::default());
+    let x = frame.cols() - 4;
+    let y = frame.rows() - 1;
+    NewMenu::new("Game Over\nLevel 3".to_string(), x, y).draw(&mut frame);
+    assert_eq!(row(&frame, y), format!("{}Game", " ".repeat(x)));
+}
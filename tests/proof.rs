@@ -0,0 +1,62 @@
+mod common;
+
+use invaders::{game::{Command, Input}, invaders::SpeedCurve, level::{Level, LEVEL_DIR}, proof::{self, compress_inputs, decompress_inputs, rules_hash}};
+
+#[test]
+fn input_log_round_trips() {
+    let mut inputs: Vec<Input> = (1..=300).map(|tick| Input { tick: tick * 2, command: Command::MoveLeft }).collect();
+    inputs.push(Input { tick: 600, command: Command::Shoot });
+    inputs.push(Input { tick: 100_000, command: Command::MoveRight });
+    let bytes = compress_inputs(&inputs);
+    // the held key is a single run
+    assert!(bytes.len() < 16, "{} bytes", bytes.len());
+    assert_eq!(decompress_inputs(&bytes).unwrap(), inputs);
+    assert!(decompress_inputs(&[1, 1, 9]).is_err());
+    assert!(decompress_inputs(&[0xff]).is_err());
+}
+
+#[test]
+fn verifies_a_played_game() {
+    let player = common::proven_player("ann", 11);
+    assert!(player.score > 0);
+    assert_eq!(proof::verify(&player, &[], SpeedCurve::default()), Ok(()));
+}
+
+#[test]
+fn rejects_tampered_submissions() {
+    // the playback gives the real score away
+    let mut raised = common::proven_player("ann", 11);
+    let score = raised.score;
+    raised.score += 100;
+    let error = proof::verify(&raised, &[], SpeedCurve::default()).unwrap_err();
+    assert!(error.starts_with(&format!("Impossible Score, The Game Plays Back To {}", score)), "{}", error);
+
+    let mut idle = common::proven_player("ann", 11);
+    let proof = idle.proof.as_mut().unwrap();
+    proof.inputs = String::new();
+    assert!(proof::verify(&idle, &[], SpeedCurve::default()).unwrap_err().starts_with("Impossible Score"));
+
+    let mut broken = common::proven_player("ann", 11);
+    broken.proof.as_mut().unwrap().inputs = "zz".to_string();
+    assert_eq!(proof::verify(&broken, &[], SpeedCurve::default()), Err("Input Log Isn't Hex".to_string()));
+
+    let mut unproven = common::proven_player("ann", 11);
+    unproven.proof = None;
+    assert_eq!(proof::verify(&unproven, &[], SpeedCurve::default()), Err("Score Has No Proof".to_string()));
+}
+
+#[test]
+fn rejects_games_played_with_other_levels_or_speed_curve() {
+    let player = common::proven_player("ann", 11);
+    let levels = Level::load_dir(LEVEL_DIR).unwrap();
+    assert!(!levels.is_empty());
+    let different = Err("Score Played With Different Levels Or Speed Curve".to_string());
+    // the checker's own definitions differ from the ones the game was played with
+    assert_eq!(proof::verify(&player, &levels, SpeedCurve::default()), different);
+    assert_eq!(proof::verify(&player, &[], SpeedCurve::constant()), different);
+
+    // a proof claiming easier definitions than it was played with
+    let mut easier = common::proven_player("ann", 11);
+    easier.proof.as_mut().unwrap().rules = rules_hash(&[], SpeedCurve::constant());
+    assert_eq!(proof::verify(&easier, &[], SpeedCurve::default()), different);
+}
@@ -0,0 +1,47 @@
+mod common;
+
+use std::{env, fs, process};
+use invaders::{invaders::SpeedCurve, level::Level, replay::Replay};
+
+#[test]
+fn recorded_game_plays_back_the_same() {
+    let game = common::played_game(7);
+    let replay = Replay::from_game(&game);
+    assert!(replay.verify(&[], SpeedCurve::default()));
+    let played = replay.simulate();
+    assert_eq!((played.player.score, played.player.level, played.tick), (game.player.score, game.player.level, game.tick));
+    assert_eq!(played.inputs, game.inputs);
+}
+
+#[test]
+fn saved_replay_plays_back_the_same() {
+    let dir = env::temp_dir().join(format!("invaders-replays-{}", process::id()));
+    let mut game = common::played_game(9);
+    game.player.name = "ann".to_string();
+    let path = Replay::from_game(&game).save(&dir.to_string_lossy()).unwrap();
+    let loaded = Replay::load(&path.to_string_lossy()).unwrap();
+    assert!(loaded.verify(&[], SpeedCurve::default()));
+    assert_eq!((loaded.score, loaded.ticks), (game.player.score, game.tick));
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn changed_inputs_are_caught() {
+    let mut replay = Replay::from_game(&common::played_game(7));
+    // never shooting can't score what the recording did
+    replay.inputs.clear();
+    assert!(!replay.verify(&[], SpeedCurve::default()));
+}
+
+#[test]
+fn edited_levels_are_caught() {
+    let mut replay = Replay::from_game(&common::played_game(7));
+    // a single slow invader, and the results it plays back to, so the file agrees with itself
+    let easy: Level = serde_json::from_str(r#"{ "formation": ["s"], "move_interval": 60000, "fire_interval": 60000 }"#).unwrap();
+    replay.levels = vec![easy];
+    let played = replay.simulate();
+    replay.score = played.player.score;
+    replay.level = played.player.level;
+    assert!(replay.verify(&replay.levels.clone(), replay.speed_curve));
+    assert!(!replay.verify(&[], SpeedCurve::default()));
+}
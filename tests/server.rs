@@ -0,0 +1,295 @@
+mod common;
+
+use std::{env, fs, process, thread, io::{Read, Write}, net::TcpListener, sync::{Arc, atomic::{AtomicUsize, Ordering}}};
+use invaders::{level::{Level, LEVEL_DIR}, request::{ReqClient, ClientConfig, LeaderboardError}, scores, server, system::{SystemPlayer, SubmitResponse, Period}};
+
+const API_KEY: &str = "test-key";
+
+/**
+ * desc: a fresh score file for one test
+ */
+fn data_path(test: &str) -> String {
+    let path = env::temp_dir().join(format!("invaders-{}-{}.json", test, process::id()));
+    let _ = fs::remove_file(&path);
+    path.to_string_lossy().to_string()
+}
+
+/**
+ * desc: starts a server on a free port that takes scores without a proof, returns a client for it
+ */
+fn start(path: &str, api_key: &str) -> ReqClient {
+    serve(path, api_key, true)
+}
+
+fn serve(path: &str, api_key: &str, allow_unproven: bool) -> ReqClient {
+    let (addr, server) = server::bind("127.0.0.1:0", path, API_KEY.to_string(), allow_unproven, Vec::new()).unwrap();
+    tokio::spawn(server);
+    ReqClient::with_url(format!("http://{}", addr)).with_key(api_key.to_string())
+}
+
+fn player(name: &str, score: u32) -> SystemPlayer {
+    SystemPlayer { name: name.to_string(), score, is: "score".to_string(), proof: None }
+}
+
+#[tokio::test]
+async fn submits_and_ranks_scores() {
+    let path = data_path("ranks");
+    let client = start(&path, API_KEY);
+    let first = client.update_scores(player("ann", 50)).await.unwrap();
+    assert_eq!(first, SubmitResponse { score: 50, rank: 1, total: 1, previous_best: None, new_high_score: true });
+    assert_eq!(first.to_string(), "First score on the board! Rank #1 of 1");
+    let second = client.update_scores(player("bob", 70)).await.unwrap();
+    assert_eq!((second.rank, second.total), (1, 2));
+    let improved = client.update_scores(player("ann", 90)).await.unwrap();
+    assert_eq!(improved, SubmitResponse { score: 90, rank: 1, total: 2, previous_best: Some(50), new_high_score: true });
+    assert_eq!(improved.to_string(), "New personal best! Rank #1 of 2");
+    let scores = client.get_scores().await.unwrap();
+    let names: Vec<&str> = scores.iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(names, ["ann", "bob"]);
+    let _ = fs::remove_file(&path);
+}
+
+#[tokio::test]
+async fn keeps_the_best_score_per_player() {
+    let path = data_path("best");
+    let client = start(&path, API_KEY);
+    client.update_scores(player("ann", 50)).await.unwrap();
+    let response = client.update_scores(player("ann", 20)).await.unwrap();
+    assert_eq!(response, SubmitResponse { score: 20, rank: 1, total: 1, previous_best: Some(50), new_high_score: false });
+    assert_eq!(response.to_string(), "Scored 20, your best is still 50\nRank #1 of 1");
+    let scores = client.get_scores().await.unwrap();
+    assert_eq!(scores.len(), 1);
+    assert_eq!(scores[0].score, 50);
+    let _ = fs::remove_file(&path);
+}
+
+#[tokio::test]
+async fn rejects_a_wrong_api_key() {
+    let path = data_path("key");
+    let client = start(&path, "wrong-key");
+    assert_eq!(client.get_scores().await.unwrap_err(), LeaderboardError::Unauthorized);
+    assert_eq!(client.update_scores(player("ann", 50)).await.unwrap_err(), LeaderboardError::Unauthorized);
+    assert!(!std::path::Path::new(&path).exists());
+}
+
+#[tokio::test]
+async fn rejects_invalid_scores() {
+    let path = data_path("invalid");
+    let (addr, server) = server::bind("127.0.0.1:0", &path, API_KEY.to_string(), false, Vec::new()).unwrap();
+    tokio::spawn(server);
+    let response = reqwest::Client::new().post(format!("http://{}", addr))
+        .header("x-api-key", API_KEY)
+        .body("{\"name\": \"ann\"}")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn rejects_an_oversized_submission() {
+    let path = data_path("oversized");
+    let (addr, server) = server::bind("127.0.0.1:0", &path, API_KEY.to_string(), false, Vec::new()).unwrap();
+    tokio::spawn(server);
+    let response = reqwest::Client::new().post(format!("http://{}", addr))
+        .header("x-api-key", API_KEY)
+        .body(vec![b' '; server::MAX_BODY + 1])
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::PAYLOAD_TOO_LARGE);
+    assert!(!std::path::Path::new(&path).exists());
+}
+
+#[tokio::test]
+async fn scores_survive_a_restart() {
+    let path = data_path("restart");
+    start(&path, API_KEY).update_scores(player("ann", 50)).await.unwrap();
+    let scores = start(&path, API_KEY).get_scores().await.unwrap();
+    assert_eq!(scores.len(), 1);
+    assert_eq!(scores[0].name, "ann");
+    let _ = fs::remove_file(&path);
+}
+
+#[tokio::test]
+async fn keeps_only_scores_that_can_still_rank() {
+    let path = data_path("prune");
+    let now = scores::now();
+    // a best of all time, a score beaten the same week and a week's best from a past week
+    let old = [("ann", 500, 1000), ("ann", 300, now - 30 * 86400), ("ann", 400, now - 29 * 86400)];
+    let old: Vec<serde_json::Value> = old.iter()
+        .map(|(name, score, date)| serde_json::json!({ "name": name, "score": score, "date": date }))
+        .collect();
+    fs::write(&path, serde_json::to_string(&old).unwrap()).unwrap();
+    let client = start(&path, API_KEY);
+    for score in [20, 60, 40] {
+        client.update_scores(player("ann", score)).await.unwrap();
+    }
+    let stored: Vec<serde_json::Value> = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+    let stored: Vec<u64> = stored.iter().map(|s| s["score"].as_u64().unwrap()).collect();
+    assert_eq!(stored, [500, 60]);
+    assert!(!std::path::Path::new(&format!("{}.tmp", path)).exists());
+    let _ = fs::remove_file(&path);
+}
+
+#[tokio::test]
+async fn reports_an_unreachable_server() {
+    // bind and drop a listener to find a port nothing is listening on
+    let addr = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+    let client = ReqClient::from_config(ClientConfig { url: format!("http://{}", addr), retries: 0, ..Default::default() });
+    let error = client.get_scores().await.unwrap_err();
+    assert_eq!(error, LeaderboardError::Network);
+    assert!(error.should_queue());
+}
+
+/**
+ * desc: a server that answers every request with 503, returns its address and how many requests it got
+ */
+fn unavailable_server() -> (String, Arc<AtomicUsize>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let requests = Arc::new(AtomicUsize::new(0));
+    let counted = requests.clone();
+    thread::spawn(move || {
+        for mut stream in listener.incoming().flatten() {
+            let _ = stream.read(&mut [0u8; 4096]);
+            counted.fetch_add(1, Ordering::SeqCst);
+            let _ = stream.write_all(b"HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\nConnection: close\r\n\r\n");
+        }
+    });
+    (format!("http://{}", addr), requests)
+}
+
+#[tokio::test]
+async fn retries_reads_but_not_submissions() {
+    let (url, requests) = unavailable_server();
+    let client = ReqClient::from_config(ClientConfig { url, retries: 2, backoff_ms: 1, ..Default::default() });
+    assert_eq!(client.get_scores().await.unwrap_err(), LeaderboardError::ServerError(503));
+    assert_eq!(requests.load(Ordering::SeqCst), 3);
+    assert_eq!(client.update_scores(player("ann", 50)).await.unwrap_err(), LeaderboardError::ServerError(503));
+    assert_eq!(requests.load(Ordering::SeqCst), 4);
+}
+
+#[tokio::test]
+async fn pages_through_the_leaderboard() {
+    let path = data_path("pages");
+    let client = start(&path, API_KEY);
+    for i in 1..=25 {
+        client.update_scores(player(&format!("p{:02}", i), i * 10)).await.unwrap();
+    }
+    let page = client.get_page(Period::All, 10, 10, None).await.unwrap();
+    assert_eq!((page.offset, page.total), (10, 25));
+    let ranks: Vec<usize> = page.scores.iter().map(|s| s.rank).collect();
+    assert_eq!(ranks, (11..=20).collect::<Vec<usize>>());
+    assert_eq!(page.scores[0].name, "p15");
+    // asking past the end gives the last full page
+    let last = client.get_page(Period::All, 40, 10, None).await.unwrap();
+    assert_eq!(last.offset, 15);
+    assert_eq!(last.scores.len(), 10);
+    let _ = fs::remove_file(&path);
+}
+
+#[tokio::test]
+async fn jumps_to_a_players_page() {
+    let path = data_path("around");
+    let client = start(&path, API_KEY);
+    for i in 1..=25 {
+        client.update_scores(player(&format!("p{:02}", i), i * 10)).await.unwrap();
+    }
+    // p03 is 23rd, on the page starting at 20 which is clamped to the last full page
+    let page = client.get_page(Period::All, 0, 10, Some("p03")).await.unwrap();
+    assert_eq!(page.offset, 15);
+    assert!(page.scores.iter().any(|s| s.name == "p03" && s.rank == 23));
+    let page = client.get_page(Period::All, 0, 5, Some("p18")).await.unwrap();
+    assert_eq!(page.offset, 5);
+    let error = client.get_page(Period::All, 0, 10, Some("nobody")).await.unwrap_err();
+    assert_eq!(error, LeaderboardError::Server("nobody Isn't On This Leaderboard".to_string()));
+    let _ = fs::remove_file(&path);
+}
+
+#[test]
+fn periods_start_on_utc_days_and_mondays() {
+    // 1970-01-01 was a Thursday, its week began before the clock did
+    assert_eq!(Period::Week.since(2 * 86400), 0);
+    assert_eq!(Period::Week.since(4 * 86400 + 5), 4 * 86400);
+    assert_eq!(Period::Week.since(10 * 86400), 4 * 86400);
+    assert_eq!(Period::Today.since(2 * 86400 + 5), 2 * 86400);
+    assert_eq!(Period::All.since(2 * 86400), 0);
+}
+
+#[tokio::test]
+async fn filters_the_leaderboard_by_period() {
+    let path = data_path("period");
+    let now = scores::now();
+    let old = [("ann", 500, 0), ("bob", 300, now - 30 * 86400), ("cat", 200, Period::Week.since(now))];
+    let old: Vec<serde_json::Value> = old.iter()
+        .map(|(name, score, date)| serde_json::json!({ "name": name, "score": score, "date": date }))
+        .collect();
+    fs::write(&path, serde_json::to_string(&old).unwrap()).unwrap();
+    let client = start(&path, API_KEY);
+    client.update_scores(player("dan", 100)).await.unwrap();
+    // a weaker score today still puts ann on today's board
+    client.update_scores(player("ann", 50)).await.unwrap();
+    let names = |page: invaders::system::ScorePage| page.scores.into_iter().map(|s| (s.name, s.score)).collect::<Vec<_>>();
+    let all = client.get_page(Period::All, 0, 10, None).await.unwrap();
+    assert_eq!(names(all), [("ann".to_string(), 500), ("bob".to_string(), 300), ("cat".to_string(), 200), ("dan".to_string(), 100)]);
+    let week = client.get_page(Period::Week, 0, 10, None).await.unwrap();
+    assert_eq!(names(week), [("cat".to_string(), 200), ("dan".to_string(), 100), ("ann".to_string(), 50)]);
+    let today = client.get_page(Period::Today, 0, 10, None).await.unwrap();
+    assert_eq!(today.period, Period::Today);
+    // cat's score is only from today when the week began today
+    let today: Vec<_> = names(today).into_iter().filter(|(name, _)| name != "cat").collect();
+    assert_eq!(today, [("dan".to_string(), 100), ("ann".to_string(), 50)]);
+    let _ = fs::remove_file(&path);
+}
+
+#[tokio::test]
+async fn accepts_a_proven_score() {
+    let path = data_path("proven");
+    let client = serve(&path, API_KEY, false);
+    let player = common::proven_player("ann", 7);
+    let score = player.score;
+    assert!(score > 0);
+    let response = client.update_scores(player).await.unwrap();
+    assert_eq!((response.score, response.rank), (score, 1));
+    let _ = fs::remove_file(&path);
+}
+
+#[tokio::test]
+async fn rejects_unproven_and_impossible_scores() {
+    let path = data_path("impossible");
+    let client = serve(&path, API_KEY, false);
+    let error = client.update_scores(player("ann", 50)).await.unwrap_err();
+    assert_eq!(error, LeaderboardError::Server("Score Rejected: Score Has No Proof".to_string()));
+    // a score that doesn't match its game fails the playback
+    let mut cheat = common::proven_player("bob", 7);
+    let score = cheat.score;
+    cheat.score += 1000;
+    let error = client.update_scores(cheat).await.unwrap_err();
+    assert!(!error.should_queue());
+    assert_eq!(error.to_string(), format!("Score Rejected: Impossible Score, The Game Plays Back To {} On Level 1", score));
+    assert!(client.get_scores().await.unwrap().is_empty());
+    let _ = fs::remove_file(&path);
+}
+
+#[tokio::test]
+async fn rejects_a_game_played_with_other_levels() {
+    let path = data_path("levels");
+    let (addr, server) = server::bind("127.0.0.1:0", &path, API_KEY.to_string(), false, Level::load_dir(LEVEL_DIR).unwrap()).unwrap();
+    tokio::spawn(server);
+    let client = ReqClient::with_url(format!("http://{}", addr)).with_key(API_KEY.to_string());
+    // the game was played on generated levels, not the server's level files
+    let error = client.update_scores(common::proven_player("ann", 7)).await.unwrap_err();
+    assert_eq!(error, LeaderboardError::Server("Score Rejected: Score Played With Different Levels Or Speed Curve".to_string()));
+    let _ = fs::remove_file(&path);
+}
+
+#[test]
+fn refuses_a_score_file_it_cant_read() {
+    // a directory where the file should be can't be read as one
+    let path = env::temp_dir().join(format!("invaders-unreadable-{}", process::id()));
+    fs::create_dir_all(&path).unwrap();
+    let error = server::bind("127.0.0.1:0", &path.to_string_lossy(), API_KEY.to_string(), false, Vec::new()).err().unwrap();
+    assert!(error.to_string().starts_with("Couldn't Read Score File"), "{}", error);
+    let _ = fs::remove_dir_all(&path);
+}
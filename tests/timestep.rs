@@ -0,0 +1,39 @@
+use std::{thread, time::Duration};
+use invaders::timestep::{FixedTimestep, FrameLimiter, TICK, MAX_CATCH_UP};
+
+#[test]
+fn hands_out_whole_ticks_for_the_time_passed() {
+    let mut timestep = FixedTimestep::new();
+    thread::sleep(TICK * 3);
+    let ticks = timestep.advance();
+    // sleeping can overrun, but never by less than asked
+    assert!((3..=MAX_CATCH_UP).contains(&ticks), "{} ticks", ticks);
+}
+
+#[test]
+fn drops_ticks_past_the_catch_up_limit() {
+    let mut timestep = FixedTimestep::new();
+    thread::sleep(TICK * (MAX_CATCH_UP + 20));
+    assert_eq!(timestep.advance(), MAX_CATCH_UP);
+    // the dropped time is gone rather than owed
+    assert!(timestep.advance() <= 1);
+}
+
+#[test]
+fn reset_forgets_the_time_passed() {
+    let mut timestep = FixedTimestep::new();
+    thread::sleep(TICK * 4);
+    timestep.reset();
+    assert_eq!(timestep.advance(), 0);
+}
+
+#[test]
+fn frame_limiter_caps_the_frame_rate() {
+    let mut limiter = FrameLimiter::new(10);
+    assert!(limiter.ready());
+    assert!(!limiter.ready());
+    thread::sleep(Duration::from_millis(110));
+    assert!(limiter.ready());
+    let mut uncapped = FrameLimiter::new(0);
+    assert!(uncapped.ready() && uncapped.ready());
+}